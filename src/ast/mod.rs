@@ -1,6 +1,9 @@
+pub mod span;
 pub mod token;
 use std::fmt::{self, Display, Formatter};
 
+use span::Span;
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct Ident(pub String);
 
@@ -30,6 +33,9 @@ pub enum Infix {
     AND,
     OR,
     XOR,
+    LogicalAnd,
+    LogicalOr,
+    NullCoalesce,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -37,43 +43,168 @@ pub enum Literal {
     Number(f64),
     String(String),
     Boolean(bool),
+    Null,
     Array(Vec<Expr>),
     Object(Vec<(Expr, Expr)>)
 }
 
+/// ## Pattern
+/// The left side of a `match` arm, a destructuring `set` or a function parameter.
+/// A pattern tests the shape of a value and binds names to the parts it matched.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Pattern {
+    /// `_`, matching anything without binding it.
+    Wildcard,
+    /// A name, matching anything and binding it.
+    Binding(Ident),
+    /// A number, string, boolean or `null`, matching an equal value.
+    Literal(Literal),
+    /// `1..5` or `1..=5`, matching numbers in the range.
+    Range(f64, f64, bool),
+    /// `[a, b, ...rest]`. Without a rest pattern the length must match exactly.
+    Array {
+        items: Vec<Pattern>,
+        rest: Option<Box<Pattern>>
+    },
+    /// `{ "key": pattern, name }`, matching objects that have at least these keys.
+    /// A bare name is short for `"name": name`.
+    Object(Vec<(String, Pattern)>),
+    /// `typeof "number"`, matching values of that type.
+    Typeof(String),
+    /// `pattern = expr` inside an array or object pattern.
+    /// When destructuring, the default is used for a missing element or key;
+    /// `match` ignores it.
+    Default(Box<Pattern>, Box<Expr>),
+}
+
+/// ## Params
+/// The parameter list of a function, such as `fun (a, [b, c], d = 1, ...rest)`.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Params {
+    /// Parameters filled from positional or named arguments.
+    /// One written `name = expr` is a `Pattern::Default`, used when the argument is left out.
+    pub positional: Vec<Pattern>,
+    /// `...name`, collecting the remaining positional arguments into an array.
+    pub rest: Option<Ident>,
+}
+
+/// ## Argument
+/// An argument in a call: `f(x, ...xs, name: v)`.
 #[derive(PartialEq, Clone, Debug)]
-pub enum Statement {
+pub enum Argument {
+    Positional(Expr),
+    /// `...array`, passing each element as a positional argument.
+    Spread(Expr),
+    /// `name: value`, filling the parameter with that name.
+    Named(Ident, Expr),
+}
+
+/// ## TemplatePart
+/// A piece of a template literal: literal text, or an expression from `${...}`.
+#[derive(PartialEq, Clone, Debug)]
+pub enum TemplatePart {
+    String(String),
+    Expr(Expr),
+}
+
+/// ## MatchArm
+/// `pattern if guard => body` in a `match` expression.
+#[derive(PartialEq, Clone, Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: BlockStatement,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+    /// The `///` comments written right before the statement.
+    /// Only `set` bindings keep them.
+    pub doc: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum StatementKind {
     Set(Ident, Expr),
-    Return(Expr),
+    /// `set [a, b] = v;` or `set { "name": n } = v;`
+    Destructure(Pattern, Expr),
+    Return(Option<Expr>),
     Expression(Expr),
     Include(String),
-    Anew(Ident, Expr),
+    /// Assigns to an existing variable, or to an element or field inside one:
+    /// `anew x = v`, `x[i] = v`, `x.field[j] = v`.
+    Anew(Expr, Expr),
+    /// Updates a variable, element or field in place: `x += v`, `a[i] *= v`, `x++`.
+    /// The target is evaluated once, so `a[f()] += 1` calls `f` a single time.
+    CompoundAssign(Expr, Infix, Expr),
     Break,
-    Continue
+    Continue,
+    /// `throw value;`
+    Throw(Expr),
+    /// `struct Point { x, y }`, declaring a struct type and its constructor.
+    Struct(Ident, Vec<Ident>),
+    /// `class Counter { count, fun inc() { ... } }`,
+    /// a struct type whose methods see the instance as `self`.
+    Class(Ident, Vec<Ident>, Vec<(Ident, Params, BlockStatement)>)
 }
 
+/// ## Precedence
+/// Binding power of operators, from loosest to tightest.
+/// The ordering follows C and JavaScript:
+///
+/// | Level          | Operators            |
+/// |----------------|----------------------|
+/// | `Ternary`      | `?:`                 |
+/// | `NullCoalesce` | `??`                 |
+/// | `LogicalOr`    | `\|\|`               |
+/// | `LogicalAnd`   | `&&`                 |
+/// | `BitOr`        | `\|`                 |
+/// | `BitXor`       | `^`                  |
+/// | `BitAnd`       | `&`                  |
+/// | `Equals`       | `==` `!=`            |
+/// | `Compare`      | `<` `>` `<=` `>=` `~` |
+/// | `Range`        | `..` `..=`           |
+/// | `Shift`        | `<<` `>>`            |
+/// | `Sum`          | `+` `-`              |
+/// | `Product`      | `*` `/` `%`          |
+/// | `Prefix`       | `-x` `+x` `!x`       |
+/// | `Call`         | `f(x)`               |
+/// | `Index`        | `a[i]` `a.b` `a?.b`  |
+///
+/// All binary operators are left-associative; the ternary is right-associative.
 #[derive(PartialEq, PartialOrd, Debug, Clone)]
 pub enum Precedence {
     Lowest,
+    Ternary,
+    NullCoalesce,
+    LogicalOr,
+    LogicalAnd,
+    BitOr,
+    BitXor,
+    BitAnd,
     Equals,
-    LessGreater,
+    Compare,
+    Range,
+    Shift,
     Sum,
     Product,
     Prefix,
     Call,
     Index,
-    In,
-    LeftShift,
-    RightShift,
-    AND,
-    OR,
-    XOR
 }
 
 pub type BlockStatement = Vec<Statement>;
 
 #[derive(PartialEq, Clone, Debug)]
-pub enum Expr {
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum ExprKind {
     Literal(Literal),
     Ident(Ident),
     Prefix(Prefix, Box<Expr>),
@@ -84,19 +215,65 @@ pub enum Expr {
         else_: Option<BlockStatement>
     },
 
+    /// `match (value) { pattern => body, ... }`
+    Match {
+        value: Box<Expr>,
+        arms: Vec<MatchArm>
+    },
+
+    /// `` `Hello ${name}` ``, joining its parts into a string.
+    Template(Vec<TemplatePart>),
+
+    /// `cond ? then : else_`
+    Ternary {
+        cond: Box<Expr>,
+        then: Box<Expr>,
+        else_: Box<Expr>
+    },
+
     Fun {
-        params: Vec<Ident>,
+        params: Params,
         body: BlockStatement
     },
 
+    /// `f(args)`, or `f?.(args)` when `optional`.
+    /// The optional forms of calls, indexes, slices and member accesses
+    /// evaluate to `null` instead of failing when the value before `?.` is `null`.
     Call {
         function: Box<Expr>,
-        args: Vec<Expr>
+        args: Vec<Argument>,
+        optional: bool
     },
 
+    /// `array[index]`, or `array?.[index]` when `optional`.
     Index {
         array: Box<Expr>,
-        index: Box<Expr>
+        index: Box<Expr>,
+        optional: bool
+    },
+
+    /// `object.property`, or `object?.property` when `optional`.
+    /// Calling it, as in `value.method(args)`,
+    /// looks up a built-in method when `object` has no such field.
+    Member {
+        object: Box<Expr>,
+        property: Ident,
+        optional: bool
+    },
+
+    /// `array[start:end]`, where either bound may be left out.
+    Slice {
+        array: Box<Expr>,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+        optional: bool
+    },
+
+    /// `start..end`, or `start..=end` when `inclusive`.
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool
     },
 
     Typeof {
@@ -105,13 +282,76 @@ pub enum Expr {
 
     Loop {
         body: BlockStatement
+    },
+
+    While {
+        cond: Box<Expr>,
+        body: BlockStatement
+    },
+
+    /// `try { } catch (e) { } finally { }`, where either the `catch` or the `finally` may be left out.
+    /// The catch clause handles both thrown values and runtime errors;
+    /// its binding, which is optional, names the error object.
+    Try {
+        body: BlockStatement,
+        catch: Option<(Option<Ident>, BlockStatement)>,
+        finally: Option<BlockStatement>
+    },
+
+    /// `for (item in iterable) { }` or `for (key, item in iterable) { }`.
+    For {
+        key: Option<Ident>,
+        item: Ident,
+        iterable: Box<Expr>,
+        body: BlockStatement
     }
 
 }
 
+/// ## Comment
+/// A comment in the source, kept out of the token stream
+/// so that tools such as formatters can still see it.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Comment {
+    /// The comment as written, including its `//` or `/* */` delimiters.
+    /// Doc comments hold only the text after `///`.
+    pub text: String,
+    pub doc: bool,
+    pub span: Span,
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct Program {
-    pub(crate) statements: Vec<Statement>
+    pub statements: Vec<Statement>,
+    /// Every comment in the source, in order.
+    pub comments: Vec<Comment>,
+}
+
+impl Statement {
+    pub fn new(kind: StatementKind, span: Span) -> Self {
+        Statement {
+            kind,
+            span,
+            doc: None,
+        }
+    }
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+
+    /// Whether the expression names a place that can be assigned to:
+    /// a variable, or an index or field inside one, without optional links.
+    pub fn is_assignable(&self) -> bool {
+        match &self.kind {
+            ExprKind::Ident(_) => true,
+            ExprKind::Index { array, optional: false, .. } => array.is_assignable(),
+            ExprKind::Member { object, optional: false, .. } => object.is_assignable(),
+            _ => false,
+        }
+    }
 }
 
 // Implement the Display trait for all the types we have
@@ -145,13 +385,59 @@ impl Display for Infix {
             Infix::RightShift => write!(f, ">>"),
             Infix::AND => write!(f, "&"),
             Infix::OR => write!(f, "|"),
-            Infix::XOR => write!(f, "^")
+            Infix::XOR => write!(f, "^"),
+            Infix::LogicalAnd => write!(f, "&&"),
+            Infix::LogicalOr => write!(f, "||"),
+            Infix::NullCoalesce => write!(f, "??")
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Binding(Ident(name)) => write!(f, "{}", name),
+            Pattern::Literal(Literal::Number(n)) => write!(f, "{}", n),
+            Pattern::Literal(Literal::String(s)) => write!(f, "{:?}", s),
+            Pattern::Literal(Literal::Boolean(b)) => write!(f, "{}", b),
+            Pattern::Literal(Literal::Null) => write!(f, "null"),
+            Pattern::Literal(_) => write!(f, "..."),
+            Pattern::Range(start, end, inclusive) => {
+                write!(f, "{}{}{}", start, if *inclusive { "..=" } else { ".." }, end)
+            }
+            Pattern::Array { items, rest } => {
+                let mut parts: Vec<String> = items.iter().map(|p| p.to_string()).collect();
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", rest));
+                }
+                write!(f, "[{}]", parts.join(", "))
+            }
+            Pattern::Object(entries) => {
+                let parts: Vec<String> = entries
+                    .iter()
+                    .map(|(key, pattern)| format!("{:?}: {}", key, pattern))
+                    .collect();
+                write!(f, "{{{}}}", parts.join(", "))
+            }
+            Pattern::Typeof(name) => write!(f, "typeof {:?}", name),
+            Pattern::Default(pattern, _) => write!(f, "{} = ...", pattern),
+        }
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut parts: Vec<String> = self.positional.iter().map(|p| p.to_string()).collect();
+        if let Some(Ident(rest)) = &self.rest {
+            parts.push(format!("...{}", rest));
         }
+        write!(f, "{}", parts.join(", "))
     }
 }
 
 impl Display for Expr {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{:?}", self.kind)
     }
 }
\ No newline at end of file
@@ -0,0 +1,50 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+};
+
+/// ## Span
+/// A region of source code.
+/// `start` and `end` are byte offsets into the source,
+/// `line` and `column` are 1-based and point at `start`.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Span {
+    pub file: Rc<str>,
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(file: Rc<str>, line: usize, column: usize, start: usize, end: usize) -> Self {
+        Span {
+            file,
+            line,
+            column,
+            start,
+            end,
+        }
+    }
+
+    /// Returns a span starting at `self` and ending at `other`.
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            file: self.file.clone(),
+            line: self.line,
+            column: self.column,
+            start: self.start,
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.file.is_empty() {
+            write!(f, "{}:{}", self.line, self.column)
+        } else {
+            write!(f, "{}:{}:{}", self.file, self.line, self.column)
+        }
+    }
+}
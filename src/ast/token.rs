@@ -1,19 +1,39 @@
 use std::fmt::{self, Display, Formatter};
 
+use super::{span::Span, Infix};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq)]
-pub enum Token {
+pub enum TokenKind {
     Eof,
     Illegal,
-    Comment,
+    Comment(String),
+    DocComment(String),
 
     // Literals
     Ident(String),
     Number(f64),
     String(String),
     Boolean(bool),
+    Null,
+    /// Text of a template literal from its opening backtick up to the first `${`.
+    TemplateStart(String),
+    /// Text of a template literal between a `}` and the next `${`.
+    TemplateMiddle(String),
+    /// Text of a template literal from the last `}` up to its closing backtick.
+    TemplateEnd(String),
 
     // Operators
     Assign,
+    /// `+=`, `-=`, `<<=` and the like, holding the operator they apply.
+    CompoundAssign(Infix),
+    Increment,
+    Decrement,
     Plus,
     Minus,
     Bang,
@@ -22,6 +42,14 @@ pub enum Token {
     Percent,
     Anew,
     In,
+    Dot,
+    DotDot,
+    DotDotEqual,
+
+    // Logical operators
+    LogicalAnd,
+    LogicalOr,
+    NullCoalesce,
 
     // Bitwise operators
     AND,
@@ -41,6 +69,11 @@ pub enum Token {
     // Delimiters
     Comma,
     Colon,
+    Question,
+    /// `?.`, starting an optional member access, index or call.
+    QuestionDot,
+    FatArrow,
+    Ellipsis,
     Semicolon,
     LeftParen,
     RightParen,
@@ -58,14 +91,35 @@ pub enum Token {
     Include,
     Typeof,
     Loop,
+    Match,
+    While,
+    For,
     Break,
-    Continue
+    Continue,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Struct,
+    Class
 
 }
 
-impl Display for Token {
+impl Token {
+    pub fn new(kind: TokenKind, span: Span) -> Self {
+        Token { kind, span }
+    }
+}
+
+impl Display for TokenKind {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
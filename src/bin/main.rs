@@ -14,7 +14,7 @@ fn main() {
         }
         let content = fs::read_to_string(&args[2]).expect("Could not read file.");
 
-        ethereal_lang::interpret(content.as_str());
+        ethereal_lang::interpret_file(&args[2], content.as_str());
     } else {
     
         println!(
@@ -0,0 +1,220 @@
+use crate::ast::span::Span;
+use serde_json::{json, Value};
+use std::fmt::{self, Display, Formatter};
+
+/// Unexpected token while parsing.
+pub const UNEXPECTED_TOKEN: &str = "E0001";
+/// A function parameter that is not an identifier.
+pub const INVALID_PARAMETER: &str = "E0002";
+/// A token that cannot start an expression.
+pub const EXPECTED_EXPRESSION: &str = "E0003";
+/// A string literal without its closing quote.
+pub const UNTERMINATED_STRING: &str = "E0004";
+/// An unknown or malformed escape sequence in a string literal.
+pub const INVALID_ESCAPE: &str = "E0005";
+/// A number literal that cannot be read, such as `1.2.3` or `0xZZ`.
+pub const INVALID_NUMBER: &str = "E0006";
+/// A `/*` comment without its closing `*/`.
+pub const UNTERMINATED_COMMENT: &str = "E0007";
+/// The left side of an assignment is not a variable, index or field.
+pub const INVALID_ASSIGNMENT: &str = "E0008";
+/// A token that cannot start or continue a pattern.
+pub const INVALID_PATTERN: &str = "E0009";
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// ## Label
+/// A span of source with a message attached to it.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// ## Diagnostic
+/// A message about the source code, such as a parse error.
+/// A diagnostic points at a primary span and can carry
+/// secondary labels and free-form notes.
+/// It can be rendered rustc-style against the source it came from,
+/// or serialized to JSON for editors.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+    /// Text shown under the primary span.
+    pub label: Option<String>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: String, span: Span) -> Self {
+        Diagnostic {
+            severity,
+            code,
+            message,
+            span,
+            label: None,
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn error(code: &'static str, message: String, span: Span) -> Self {
+        Diagnostic::new(Severity::Error, code, message, span)
+    }
+
+    /// Sets the text shown under the primary span.
+    pub fn with_primary_label(mut self, message: &str) -> Self {
+        self.label = Some(message.to_string());
+        self
+    }
+
+    /// Adds a secondary label.
+    pub fn with_label(mut self, span: Span, message: &str) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.to_string(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// ## render
+    /// Renders the diagnostic with the offending source lines.
+    /// # Arguments
+    /// * `source` - The source the spans point into.
+    /// # Returns
+    /// `String` - The rendered diagnostic.
+    /// # Examples
+    /// ```text
+    /// error[E0001]: expected next token to be Assign, got Number(1.0) instead
+    ///  --> main.etrl:1:7
+    ///   |
+    /// 1 | set x 1;
+    ///   |       ^ expected Assign
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut spans = vec![&self.span];
+        spans.extend(self.labels.iter().map(|l| &l.span));
+        let gutter = spans.iter().map(|s| s.line).max().unwrap_or(1).to_string().len();
+        let pad = " ".repeat(gutter);
+
+        let mut out = format!("{}[{}]: {}\n", self.severity, self.code, self.message);
+        out.push_str(&format!("{}--> {}\n", pad, self.span));
+        out.push_str(&format!("{} |\n", pad));
+
+        let mut labels = vec![(&self.span, '^', self.label.as_deref().unwrap_or(""))];
+        labels.extend(self.labels.iter().map(|l| (&l.span, '-', l.message.as_str())));
+        labels.sort_by_key(|(span, _, _)| (span.line, span.column));
+
+        for (span, marker, message) in labels {
+            let line = match lines.get(span.line.saturating_sub(1)) {
+                Some(l) => *l,
+                None => "",
+            };
+            out.push_str(&format!("{:>width$} | {}\n", span.line, line, width = gutter));
+            let snippet = source.get(span.start..span.end).unwrap_or("");
+            out.push_str(&format!("{} | {}\n", pad, underline(line, snippet, span, marker, message)));
+        }
+
+        for note in self.notes.iter() {
+            out.push_str(&format!("{} = note: {}\n", pad, note));
+        }
+        out
+    }
+
+    /// ## to_json
+    /// Serializes the diagnostic for editors.
+    /// # Returns
+    /// `Value` - The diagnostic as a JSON object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "severity": self.severity.to_string(),
+            "code": self.code,
+            "message": self.message,
+            "span": span_to_json(&self.span),
+            "label": self.label,
+            "labels": self.labels.iter().map(|l| json!({
+                "span": span_to_json(&l.span),
+                "message": l.message,
+            })).collect::<Vec<_>>(),
+            "notes": self.notes,
+        })
+    }
+}
+
+/// Renders every diagnostic in `diagnostics` against `source`.
+pub fn render_diagnostics(diagnostics: &[Diagnostic], source: &str) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.render(source))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Serializes every diagnostic in `diagnostics` as a JSON array.
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> Value {
+    Value::Array(diagnostics.iter().map(|d| d.to_json()).collect())
+}
+
+fn span_to_json(span: &Span) -> Value {
+    json!({
+        "file": &*span.file,
+        "line": span.line,
+        "column": span.column,
+        "start": span.start,
+        "end": span.end,
+    })
+}
+
+/// Builds the marker line under `line`, keeping tabs so the markers stay aligned.
+fn underline(line: &str, snippet: &str, span: &Span, marker: char, message: &str) -> String {
+    let offset: String = line
+        .chars()
+        .take(span.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let remaining = line.chars().count().saturating_sub(offset.chars().count());
+    let width = snippet
+        .chars()
+        .take_while(|c| *c != '\n')
+        .count()
+        .min(remaining)
+        .max(1);
+
+    let mut out = format!("{}{}", offset, marker.to_string().repeat(width));
+    if !message.is_empty() {
+        out.push(' ');
+        out.push_str(message);
+    }
+    out
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}: {}[{}]: {}", self.span, self.severity, self.code, self.message)
+    }
+}
@@ -6,22 +6,18 @@ use std::collections::HashMap;
 /// The global environment is a HashMap of names to objects.
 pub fn new_globals() -> HashMap<String, Object> {
     let mut globals = HashMap::new();
-    globals.insert(String::from("put"), Object::Inbuilt(log));
+    globals.insert(String::from("put"), Object::Inbuilt(log, Arity::at_least(1)));
     globals
 }
 
 /// The built-in function `log`.
-/// It takes an unlimited number of arguments,
+/// It takes one or more arguments,
 /// and logs them to the console.
 /// It returns `null`.
 fn log(args: Vec<Object>) -> Object {
-    if args.is_empty() {
-        return Object::Error(String::from("Wrong number of arguments"));
-    } else {
-        for arg in args {
-            print!("{} ", arg);
-        }
-        println!();
+    for arg in args {
+        print!("{} ", arg);
     }
+    println!();
     Object::Null
 }
\ No newline at end of file
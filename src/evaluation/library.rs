@@ -1,5 +1,5 @@
 use super::{store::Store, object::*, Eval};
-use crate::{lexer::Lexer, parser::Parser, std_library::*};
+use crate::{diagnostic::render_diagnostics, lexer::Lexer, parser::Parser, std_library::*};
 use std::{cell::RefCell, collections::{HashMap}, fs, rc::Rc};
 use std::io::Read;
 /// Function to load an external file or a standard library onto the environment.\
@@ -26,7 +26,7 @@ pub fn load_etrl(lib: String) -> Option<HashMap<String, Object>> {
         let mut eval = Eval::new(Rc::new(RefCell::new(Store::new())));
         // Evaluates the program.
         eval.eval(program);
-        let store = (&*eval.store.borrow()).to_owned().store;
+        let store = (*eval.store.borrow()).to_owned().store;
         let mut final_env = HashMap::new();
         // Returns the environment with the library loaded.
         for (k, v) in store.iter() {
@@ -48,7 +48,7 @@ pub fn load_etrl(lib: String) -> Option<HashMap<String, Object>> {
                 let mut parser = Parser::new(Lexer::new(s.to_string()));
                 let program = parser.parse_program();
                 eval.eval(program);
-                let store = (&*eval.store.borrow()).to_owned().store;
+                let store = (*eval.store.borrow()).to_owned().store;
                 let mut final_env = HashMap::new();
                 for (k, v) in libs.globals.iter() {
                     final_env.insert(k.to_string(), v.to_owned());
@@ -63,19 +63,17 @@ pub fn load_etrl(lib: String) -> Option<HashMap<String, Object>> {
     }
     let filename =format!("./{}.etrl", lib);
     // File is read as a string.
-    let file = fs::read_to_string(filename).expect("Lib not found.");
-    let mut parser = Parser::new(Lexer::new(file));
+    let file = fs::read_to_string(&filename).expect("Lib not found.");
+    let mut parser = Parser::new(Lexer::new_with_file(file.clone(), &filename));
     let program = parser.parse_program();
     if !parser.errors.is_empty() {
-        for e in parser.errors.iter() {
-            println!("\t{}", e);
-        }
+        println!("{}", render_diagnostics(&parser.errors, &file));
         return None;
     };
     let mut eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     // Evaluates the program.
     eval.eval(program);
-    let store = (&*eval.store.borrow()).to_owned().store;
+    let store = (*eval.store.borrow()).to_owned().store;
     let mut final_env = HashMap::new();
     // Returns the environment with the library loaded.
     for (k, v) in store.iter() {
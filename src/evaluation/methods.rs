@@ -0,0 +1,148 @@
+use super::object::{Arity, InbuiltFunction, Object};
+use crate::std_library::{array, math, string, util};
+
+/// ## find_method
+/// Looks up a built-in method such as `"abc".upper()` or `[1, 2].push(3)`.
+/// Methods are inbuilt functions that take the receiver as their first argument,
+/// so the std library functions double as methods.
+/// Built-in methods never mutate the receiver:
+/// `arr.push(1)` returns a new array and leaves `arr` as it was.
+/// Only the methods of a class can change their receiver, through `self`.
+/// # Arguments
+/// * `receiver` - The value the method is called on.
+/// * `name` - The name of the method.
+/// # Returns
+/// `Option<(InbuiltFunction, Arity)>` - The method and its arity counting the receiver,
+/// if the receiver's type has one with that name.
+pub fn find_method(receiver: &Object, name: &str) -> Option<(InbuiltFunction, Arity)> {
+    let (method, arity): (InbuiltFunction, usize) = match (receiver, name) {
+        (Object::String(_) | Object::Array(_), "len") => (util::length, 1),
+        (_, "to_string") => (string::to_string, 1),
+
+        (Object::String(_), "upper") => (upper, 1),
+        (Object::String(_), "lower") => (lower, 1),
+        (Object::String(_), "trim") => (trim, 1),
+        (Object::String(_), "split") => (split, 2),
+        (Object::String(_), "contains") => (contains, 2),
+        (Object::String(_), "starts_with") => (starts_with, 2),
+        (Object::String(_), "ends_with") => (ends_with, 2),
+        (Object::String(_), "replace") => (string::replace, 3),
+
+        (Object::Array(_), "push") => (array::push, 2),
+        (Object::Array(_), "pop") => (array::pop, 1),
+        (Object::Array(_), "head") => (array::head, 1),
+        (Object::Array(_), "tail") => (array::tail, 1),
+        (Object::Array(_), "includes") => (array::includes, 2),
+        (Object::Array(_), "join") => (join, 2),
+        (Object::Array(_), "reverse") => (reverse, 1),
+
+        (Object::Object(_), "len") => (object_len, 1),
+        (Object::Object(_), "keys") => (keys, 1),
+        (Object::Object(_), "values") => (values, 1),
+        (Object::Object(_), "has") => (has, 2),
+
+        (Object::Number(_), "round") => (math::round, 1),
+        (Object::Number(_), "floor") => (math::floor, 1),
+        (Object::Number(_), "ceil") => (math::ceil, 1),
+        (Object::Number(_), "abs") => (math::abs, 1),
+        (Object::Number(_), "sqrt") => (math::sqrt, 1),
+        _ => return None,
+    };
+    Some((method, Arity::exactly(arity)))
+}
+
+fn map_string(args: Vec<Object>, f: fn(&str) -> String) -> Object {
+    match &args[0] {
+        Object::String(s) => Object::String(f(s)),
+        o => Object::error(format!("Expected a string. Got {}", o)),
+    }
+}
+
+fn upper(args: Vec<Object>) -> Object {
+    map_string(args, str::to_uppercase)
+}
+
+fn lower(args: Vec<Object>) -> Object {
+    map_string(args, str::to_lowercase)
+}
+
+fn trim(args: Vec<Object>) -> Object {
+    map_string(args, |s| s.trim().to_string())
+}
+
+fn test_string(args: Vec<Object>, f: fn(&str, &str) -> bool) -> Object {
+    match (&args[0], &args[1]) {
+        (Object::String(s), Object::String(p)) => Object::Bool(f(s, p)),
+        (_, o) => Object::error(format!("Argument must be a string. Got {}", o)),
+    }
+}
+
+fn contains(args: Vec<Object>) -> Object {
+    test_string(args, |s, p| s.contains(p))
+}
+
+fn starts_with(args: Vec<Object>) -> Object {
+    test_string(args, |s, p| s.starts_with(p))
+}
+
+fn ends_with(args: Vec<Object>) -> Object {
+    test_string(args, |s, p| s.ends_with(p))
+}
+
+fn split(args: Vec<Object>) -> Object {
+    match (&args[0], &args[1]) {
+        (Object::String(s), Object::String(sep)) => Object::Array(
+            s.split(sep.as_str())
+                .map(|part| Object::String(part.to_string()))
+                .collect(),
+        ),
+        (_, o) => Object::error(format!("Separator must be a string. Got {}", o)),
+    }
+}
+
+fn join(args: Vec<Object>) -> Object {
+    match (&args[0], &args[1]) {
+        (Object::Array(a), Object::String(sep)) => Object::String(
+            a.iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<_>>()
+                .join(sep),
+        ),
+        (_, o) => Object::error(format!("Separator must be a string. Got {}", o)),
+    }
+}
+
+fn reverse(args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Array(a) => Object::Array(a.iter().rev().cloned().collect()),
+        o => Object::error(format!("Expected an array. Got {}", o)),
+    }
+}
+
+fn object_len(args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Object(hash) => Object::Number(hash.len() as f64),
+        o => Object::error(format!("Expected an object. Got {}", o)),
+    }
+}
+
+fn keys(args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Object(hash) => Object::Array(hash.keys().cloned().collect()),
+        o => Object::error(format!("Expected an object. Got {}", o)),
+    }
+}
+
+fn values(args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Object(hash) => Object::Array(hash.values().cloned().collect()),
+        o => Object::error(format!("Expected an object. Got {}", o)),
+    }
+}
+
+fn has(args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Object(hash) => Object::Bool(hash.contains_key(&args[1])),
+        o => Object::error(format!("Expected an object. Got {}", o)),
+    }
+}
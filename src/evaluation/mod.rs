@@ -2,11 +2,13 @@ pub mod object;
 pub mod store;
 pub mod library;
 pub mod globals;
+pub mod methods;
+pub mod pattern;
 
-use crate::ast::*;
+use crate::ast::{span::Span, *};
 use globals::new_globals;
 use store::Store;
-use object::Object;
+use object::{Arity, Frame, Object, StructType};
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use library::load_etrl;
@@ -20,6 +22,8 @@ use library::load_etrl;
 pub struct Eval {
     /// The current environment.
     pub store: Rc<RefCell<Store>>,
+    /// The user functions being called, outermost first.
+    pub call_stack: Vec<Frame>,
 }
 
 impl Eval {
@@ -30,7 +34,7 @@ impl Eval {
     /// # Returns
     /// `Eval` - The new Eval struct.
     pub fn new(store: Rc<RefCell<Store>>) -> Self {
-        Eval { store }
+        Eval { store, call_stack: vec![] }
     }
 
     /// ## is_truthy
@@ -40,7 +44,7 @@ impl Eval {
     /// # Returns
     /// `bool` - Whether the object is truthy.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     /// let obj = Object::Boolean(true);
@@ -57,14 +61,42 @@ impl Eval {
     /// # Returns
     /// `bool` - Whether the object is an error.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
-    /// let obj = Object::Error(String::from("Error"));
+    /// let obj = Object::error("Error");
     /// assert_eq!(true, is_error(obj));
     /// ```
     fn is_error(&mut self, object: &Object) -> bool {
-        matches!(object, Object::Error(_))
+        matches!(object, Object::Error(..) | Object::Thrown(..))
+    }
+
+    /// ## error_at
+    /// Creates an error object pointing at `span`,
+    /// followed by the stack of calls it was raised in.
+    /// # Arguments
+    /// * `span` - Where in the source the error happened.
+    /// * `msg` - The error message.
+    /// # Returns
+    /// `Object` - The error object.
+    fn error_at(&self, span: &Span, msg: String) -> Object {
+        Object::Error(msg, Some(span.clone()), self.stack_trace())
+    }
+
+    /// ## locate
+    /// Gives an error raised without a location, such as one from an inbuilt function,
+    /// the location `span` and the current stack.
+    /// Errors that already have a location, thrown values and other objects are returned as they are.
+    fn locate(&self, object: Object, span: &Span) -> Object {
+        match object {
+            Object::Error(msg, None, _) => self.error_at(span, msg),
+            o => o,
+        }
+    }
+
+    /// The frames of the call stack, innermost first.
+    fn stack_trace(&self) -> Vec<Frame> {
+        self.call_stack.iter().rev().cloned().collect()
     }
 
     /// ## eval
@@ -77,7 +109,7 @@ impl Eval {
     /// # Returns
     /// `Object` - The result of the evaluation.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     /// let program = Program {
@@ -96,7 +128,7 @@ impl Eval {
 
         for statement in program.statements {
             match self.eval_statement(statement) {
-                Some(Object::Error(val)) => return Some(Object::Error(val)),
+                Some(o @ (Object::Error(..) | Object::Thrown(..))) => return Some(o),
                 Some(Object::Return(val)) => return Some(*val),
                 e => result = e,
             }
@@ -114,7 +146,7 @@ impl Eval {
     /// # Returns
     /// `Option<Object>` - The result of the evaluation.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     /// let statement = Statement::Expression(Expression::Identifier(String::from("x")));
@@ -123,54 +155,283 @@ impl Eval {
     /// assert_eq!(Some(Object::Null), result);
     /// ```
     fn eval_statement(&mut self, statement: Statement) -> Option<Object> {
-        match statement {
-            Statement::Expression(e) => self.eval_expr(e),
-            Statement::Return(e) => {
-                let val = match self.eval_expr(e) {
-                    Some(v) => v,
-                    None => return None,
+        let span = statement.span;
+        match statement.kind {
+            StatementKind::Expression(e) => self.eval_expr(e),
+            StatementKind::Return(e) => {
+                let val = match e {
+                    Some(e) => self.eval_expr(e)?,
+                    None => Object::Null,
                 };
 
                 Some(Object::Return(Box::new(val)))
             }
-            Statement::Set(i, v) => {
-                let val = match self.eval_expr(v) {
-                    Some(value) => value,
-                    None => return None,
-                };
+            StatementKind::Set(i, v) => {
+                let mut val = self.eval_expr(v)?;
                 if self.is_error(&val) {
                     Some(val)
                 } else {
                     let Ident(name) = i;
+                    if let Object::Fn(.., fn_name @ None) = &mut val {
+                        *fn_name = Some(name.clone());
+                    }
                     self.store.borrow_mut().set(name, val);
                     None
                 }
             }
-            Statement::Anew(i, v) => {
-                let Ident(name) = i;
-                let val = match self.eval_expr(v) {
-                    Some(value) => value,
-                    None => return None,
-                };
+            StatementKind::Destructure(pattern, v) => {
+                let val = self.eval_expr(v)?;
+                if self.is_error(&val) {
+                    return Some(val);
+                }
+                let mut bindings = vec![];
+                if let Err(e) = self.destructure(&pattern, val, &mut bindings) {
+                    return Some(self.locate(e, &span));
+                }
+                for (name, value) in bindings {
+                    self.store.borrow_mut().set(name, value);
+                }
+                None
+            }
+            StatementKind::Anew(target, v) => {
+                let val = self.eval_expr(v)?;
                 if self.is_error(&val) {
                     Some(val)
                 } else {
-                    let mut store = self.store.borrow_mut();
-                    match store.get(&name) {
-                        Some(_) => {
-                            store.anew(name, val);
-                            None
-                        }
-                        None => Some(Object::Error(format!("identifier not found: {}", name))),
-                    }
+                    self.eval_assignment(target, val, &span)
                 }
             }
-            Statement::Include(i) => {
+            StatementKind::CompoundAssign(target, op, v) => {
+                self.eval_compound_assignment(target, op, v, &span)
+            }
+            StatementKind::Throw(e) => {
+                let value = self.eval_expr(e).unwrap_or(Object::Null);
+                if self.is_error(&value) {
+                    return Some(value);
+                }
+                Some(Object::Thrown(Box::new(value), span, self.stack_trace()))
+            }
+            StatementKind::Struct(name, fields) => self.declare_type(name, fields, vec![], &span),
+            StatementKind::Class(name, fields, methods) => self.declare_type(name, fields, methods, &span),
+            StatementKind::Include(i) => {
                 let lib = i;
                 self.extend_global_store(lib)
             },
-            Statement::Break => Some(Object::Break),
-            Statement::Continue => Some(Object::Continue),
+            StatementKind::Break => Some(Object::Break),
+            StatementKind::Continue => Some(Object::Continue),
+        }
+    }
+
+    /// ## declare_type
+    /// Binds the constructor of a `struct` or `class` declaration.
+    /// Methods close over the scope of the declaration, like `fun` expressions do,
+    /// and are named `Type.method` in stack traces.
+    /// # Arguments
+    /// * `name` - The name of the type.
+    /// * `fields` - The declared fields, in order.
+    /// * `methods` - The methods of a class; empty for a struct.
+    /// * `span` - The span of the declaration, for errors.
+    /// # Returns
+    /// `Option<Object>` - `None`, or an error for a field or method declared twice.
+    fn declare_type(
+        &mut self,
+        Ident(name): Ident,
+        fields: Vec<Ident>,
+        methods: Vec<(Ident, Params, BlockStatement)>,
+        span: &Span,
+    ) -> Option<Object> {
+        let mut names: Vec<String> = vec![];
+        for Ident(field) in fields {
+            if names.contains(&field) {
+                return Some(self.error_at(span, format!("duplicate field {} in {}", field, name)));
+            }
+            names.push(field);
+        }
+        let mut functions = HashMap::new();
+        for (Ident(method), params, body) in methods {
+            if names.contains(&method) || functions.contains_key(&method) {
+                return Some(self.error_at(span, format!("duplicate method {} in {}", method, name)));
+            }
+            let function = Object::Fn(params, body, Rc::clone(&self.store), Some(format!("{}.{}", name, method)));
+            functions.insert(method, function);
+        }
+        let ty = StructType { name: name.clone(), fields: names, methods: functions };
+        self.store.borrow_mut().set(name, Object::Constructor(Rc::new(ty)));
+        None
+    }
+
+    /// ## eval_assignment
+    /// Assigns a value to a variable, or to an element or field nested inside one.
+    /// Arrays and objects are values, so `x[i] = v` builds an updated copy of `x`
+    /// and rebinds `x` to it: other variables holding the old value do not change.
+    /// # Arguments
+    /// * `target` - A variable, or index expressions rooted at one.
+    /// * `value` - The value to assign.
+    /// * `span` - The span of the assignment, for errors.
+    /// # Returns
+    /// `Option<Object>` - `None`, or the error that prevented the assignment.
+    fn eval_assignment(&mut self, target: Expr, value: Object, span: &Span) -> Option<Object> {
+        let (name, path) = match self.resolve_place(target, span) {
+            Ok(place) => place,
+            Err(e) => return Some(e),
+        };
+        self.assign_place(name, &path, value, span)
+    }
+
+    /// ## eval_compound_assignment
+    /// Evaluates `target op= value`, and `target++` or `target--`.
+    /// The keys in the target are evaluated once and used both to read the old value
+    /// and to write the new one.
+    /// # Arguments
+    /// * `target` - A variable, or index expressions rooted at one.
+    /// * `op` - The operator combining the old value with `value`.
+    /// * `value` - The right-hand side.
+    /// * `span` - The span of the assignment, for errors.
+    /// # Returns
+    /// `Option<Object>` - `None`, or the error that prevented the assignment.
+    fn eval_compound_assignment(&mut self, target: Expr, op: Infix, value: Expr, span: &Span) -> Option<Object> {
+        let (name, path) = match self.resolve_place(target, span) {
+            Ok(place) => place,
+            Err(e) => return Some(e),
+        };
+        let current = self.read_place(&name, &path, span);
+        if self.is_error(&current) {
+            return Some(current);
+        }
+
+        let value = self.eval_expr(value)?;
+        if self.is_error(&value) {
+            return Some(value);
+        }
+        let updated = self.eval_infix_expr(op, current, value);
+        let updated = self.locate(updated, span);
+        if self.is_error(&updated) {
+            return Some(updated);
+        }
+        self.assign_place(name, &path, updated, span)
+    }
+
+    /// ## resolve_place
+    /// Splits an assignment target into the variable it is rooted at
+    /// and the keys leading to the element or field inside it.
+    /// Each index expression is evaluated here, exactly once.
+    /// # Arguments
+    /// * `target` - A variable, or index expressions rooted at one.
+    /// * `span` - The span of the assignment, for errors.
+    /// # Returns
+    /// `Result<(String, Vec<Object>), Object>` - The variable's name and the keys, outermost first,
+    /// or the error raised while evaluating a key.
+    fn resolve_place(&mut self, target: Expr, span: &Span) -> Result<(String, Vec<Object>), Object> {
+        let mut keys = vec![];
+        let mut target = target;
+        let name = loop {
+            match target.kind {
+                ExprKind::Ident(Ident(name)) => break name,
+                ExprKind::Index { array, index, .. } => {
+                    keys.push(*index);
+                    target = *array;
+                }
+                ExprKind::Member { object, property: Ident(property), .. } => {
+                    let span = target.span;
+                    keys.push(Expr::new(ExprKind::Literal(Literal::String(property)), span));
+                    target = *object;
+                }
+                _ => return Err(self.error_at(span, String::from("invalid assignment target"))),
+            }
+        };
+
+        let mut path = vec![];
+        for key in keys.into_iter().rev() {
+            let key = self.eval_expr(key).unwrap_or(Object::Null);
+            if self.is_error(&key) {
+                return Err(key);
+            }
+            path.push(key);
+        }
+        Ok((name, path))
+    }
+
+    /// ## read_place
+    /// Reads the element or field at `path` inside the variable `name`.
+    /// # Arguments
+    /// * `name` - The variable the place is rooted at.
+    /// * `path` - The keys leading to the element or field, as returned by `resolve_place`.
+    /// * `span` - The span of the expression, for errors.
+    /// # Returns
+    /// `Object` - The value at that place, or the error raised while reading it.
+    fn read_place(&mut self, name: &str, path: &[Object], span: &Span) -> Object {
+        let mut current = match self.store.borrow_mut().get(name) {
+            Some(current) => current,
+            None => return self.error_at(span, format!("identifier not found: {}", name)),
+        };
+        for key in path {
+            current = self.eval_index_expr(current, key.clone(), span);
+            if self.is_error(&current) {
+                break;
+            }
+        }
+        current
+    }
+
+    /// ## assign_place
+    /// Stores `value` at `path` inside the variable `name`.
+    /// # Arguments
+    /// * `name` - The variable being assigned to.
+    /// * `path` - The keys leading to the element or field, as returned by `resolve_place`.
+    /// * `value` - The value to assign.
+    /// * `span` - The span of the assignment, for errors.
+    /// # Returns
+    /// `Option<Object>` - `None`, or the error that prevented the assignment.
+    fn assign_place(&mut self, name: String, path: &[Object], value: Object, span: &Span) -> Option<Object> {
+        let current = match self.store.borrow_mut().get(&name) {
+            Some(current) => current,
+            None => return Some(self.error_at(span, format!("identifier not found: {}", name))),
+        };
+        match self.assign_path(current, path, value) {
+            Ok(updated) => {
+                self.store.borrow_mut().anew(name, updated);
+                None
+            }
+            Err(msg) => Some(self.error_at(span, msg)),
+        }
+    }
+
+    /// ## assign_path
+    /// Returns a copy of `container` with the element at `path` replaced by `value`.
+    /// Negative array indexes count from the end; object keys are inserted if missing.
+    fn assign_path(&mut self, container: Object, path: &[Object], value: Object) -> Result<Object, String> {
+        let (key, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return Ok(value),
+        };
+        match (container, key) {
+            (Object::Array(mut arr), Object::Number(i)) => {
+                let len = arr.len() as f64;
+                let idx = if *i < 0.0 { len + i } else { *i };
+                if idx < 0.0 || idx >= len {
+                    return Err(format!("index out of bounds: {} (length {})", i, len));
+                }
+                let idx = idx as usize;
+                let child = std::mem::replace(&mut arr[idx], Object::Null);
+                arr[idx] = self.assign_path(child, rest, value)?;
+                Ok(Object::Array(arr))
+            }
+            (Object::Object(mut hash), Object::Number(_) | Object::Bool(_) | Object::String(_)) => {
+                let child = hash.remove(key).unwrap_or(Object::Null);
+                let child = self.assign_path(child, rest, value)?;
+                hash.insert(key.clone(), child);
+                Ok(Object::Object(hash))
+            }
+            (Object::Object(_), key) => Err(format!("unsable as hash key: {}", key)),
+            (Object::Struct(ty, mut values), Object::String(field)) => match ty.field_index(field) {
+                Some(i) => {
+                    let child = std::mem::replace(&mut values[i], Object::Null);
+                    values[i] = self.assign_path(child, rest, value)?;
+                    Ok(Object::Struct(ty, values))
+                }
+                None => Err(format!("{} has no field {}", ty.name, field)),
+            },
+            (container, key) => Err(format!("cannot assign to {}[{}]", container, key)),
         }
     }
 
@@ -184,7 +445,7 @@ impl Eval {
     /// # Returns
     /// `Option<Object>` - The result of the evaluation.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     /// let block = Block {
@@ -204,7 +465,7 @@ impl Eval {
         for statement in statements {
             match self.eval_statement(statement) {
                 Some(Object::Return(e)) => return Some(Object::Return(e)),
-                Some(Object::Error(e)) => return Some(Object::Error(e)),
+                Some(o @ (Object::Error(..) | Object::Thrown(..))) => return Some(o),
                 Some(Object::Break) => return Some(Object::Break),
                 Some(Object::Continue) => return Some(Object::Continue),
                 e => result = e,
@@ -223,7 +484,7 @@ impl Eval {
     /// # Returns
     /// `Option<Object>` - The result of the evaluation.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     /// let expr = Expression::Identifier(String::from("x"));
@@ -232,100 +493,316 @@ impl Eval {
     /// assert_eq!(Some(Object::Null), result);
     /// ```
     fn eval_expr(&mut self, expr: Expr) -> Option<Object> {
-        match expr {
-            Expr::Ident(ident) => Some(self.eval_ident(ident)),
-            Expr::Literal(lit) => Some(self.eval_literal(lit)),
-            Expr::Prefix(prefix, right) => self
-                .eval_expr(*right)
-                .map(|expr| self.eval_prefix_expr(prefix, expr)),
-            Expr::Infix(infix, left, right) => {
-                let left_expr = self.eval_expr(*left);
-                let right_expr = self.eval_expr(*right);
-                match left_expr.clone() {
-                    Some(l) => {
-                        if self.is_error(&l) {
-                            return left_expr;
-                        }
-                        if self.is_error(&right_expr.clone().unwrap()) {
-                            return right_expr;
-                        }
-                        right_expr.map(|r| self.eval_infix_expr(infix, left_expr.unwrap(), r))
-                    }
-                    _ => None,
+        let span = expr.span;
+        match expr.kind {
+            ExprKind::Ident(ident) => Some(self.eval_ident(ident, &span)),
+            ExprKind::Literal(lit) => Some(self.eval_literal(lit)),
+            ExprKind::Prefix(prefix, right) => {
+                let right = self.eval_expr(*right)?;
+                if self.is_error(&right) {
+                    return Some(right);
                 }
+                let result = self.eval_prefix_expr(prefix, right);
+                Some(self.locate(result, &span))
+            }
+            ExprKind::Infix(infix @ (Infix::LogicalAnd | Infix::LogicalOr | Infix::NullCoalesce), left, right) => {
+                self.eval_logical_expr(infix, *left, *right)
             }
-            Expr::If {
+            ExprKind::Infix(infix, left, right) => {
+                let left = self.eval_expr(*left)?;
+                if self.is_error(&left) {
+                    return Some(left);
+                }
+                let right = self.eval_expr(*right)?;
+                if self.is_error(&right) {
+                    return Some(right);
+                }
+                let result = self.eval_infix_expr(infix, left, right);
+                Some(self.locate(result, &span))
+            }
+            ExprKind::If {
                 cond: condition,
                 then: consequence,
                 else_: alternative,
             } => {
-                let cond_expr = match self.eval_expr(*condition) {
-                    Some(e) => e,
-                    None => return None,
-                };
+                let cond_expr = self.eval_expr(*condition)?;
+                if self.is_error(&cond_expr) {
+                    return Some(cond_expr);
+                }
 
-                if self.is_truthy(cond_expr) {
+                // An `if` is a value: the last expression of the branch taken,
+                // or `null` when no branch runs or the branch ends in a statement.
+                let value = if self.is_truthy(cond_expr) {
                     self.eval_block_statement(*consequence)
                 } else if let Some(a) = alternative {
                     self.eval_block_statement(a)
                 } else {
                     None
-                }
+                };
+                Some(value.unwrap_or(Object::Null))
             }
-            Expr::Fun { params, body } => Some(Object::Fn(params, body, self.store.clone())),
-            Expr::Call { function, args } => Some(self.eval_call_expr(*function, args)),
-            Expr::Index { array, index } => {
-                let obj = self.eval_expr(*array);
-                let i = self.eval_expr(*index);
-                if let Some(Object::Object(obj)) = obj {
-                    let idx = match i {
-                        Some(Object::Number(i)) => Object::Number(i),
-                        Some(Object::String(i)) => Object::String(i),
-                        Some(Object::Bool(i)) => Object::Bool(i),
-                        _ => return None,
+            ExprKind::Match { value, arms } => {
+                let value = self.eval_expr(*value)?;
+                if self.is_error(&value) {
+                    return Some(value);
+                }
+                for arm in arms {
+                    let mut bindings = vec![];
+                    if !pattern::match_pattern(&arm.pattern, &value, &mut bindings) {
+                        continue;
+                    }
+                    // Each arm binds its names in its own scope, so they shadow outer
+                    // variables and are dropped with the scope when the guard fails.
+                    let outer = Rc::clone(&self.store);
+                    self.store = Rc::new(RefCell::new(Store::new_enclosed(Rc::clone(&outer))));
+                    for (name, bound) in bindings {
+                        self.store.borrow_mut().set(name, bound);
+                    }
+                    let guard = match arm.guard {
+                        Some(guard) => self.eval_expr(guard).unwrap_or(Object::Null),
+                        None => Object::Bool(true),
                     };
-                    Some(self.eval_index_expr(Object::Object(obj.clone()), idx))
-                } else if let Some(Object::Array(arr)) = obj {
-                    let idx = match i {
-                        Some(Object::Number(i)) => Object::Number(i),
-                        _ => return None,
+                    let result = if self.is_error(&guard) {
+                        Some(guard)
+                    } else if self.is_truthy(guard) {
+                        Some(self.eval_block_statement(arm.body).unwrap_or(Object::Null))
+                    } else {
+                        None
                     };
-                    Some(self.eval_index_expr(Object::Array(arr.clone()), idx))
+                    self.store = outer;
+                    if result.is_some() {
+                        return result;
+                    }
+                }
+                Some(self.error_at(&span, format!("non-exhaustive match: no arm matches {}", value)))
+            }
+            ExprKind::Ternary { cond, then, else_ } => {
+                let cond = self.eval_expr(*cond)?;
+                if self.is_error(&cond) {
+                    return Some(cond);
+                }
+                if self.is_truthy(cond) {
+                    self.eval_expr(*then)
                 } else {
-                    None
+                    self.eval_expr(*else_)
                 }
             }
-            Expr::Typeof { expr } => Some(self.eval_typeof_expr(*expr)),
-
-            Expr::Loop { body }  => {
-                let mut _result = None;
-                loop {
-                    match self.eval_block_statement((*body).to_vec()) {
-                        Some(Object::Return(e)) => return Some(Object::Return(e)),
-                        Some(Object::Error(e)) => return Some(Object::Error(e)),
-                        Some(Object::Break) => {
-                            break Some(Object::Null);
+            ExprKind::Template(parts) => {
+                let mut result = String::new();
+                for part in parts {
+                    match part {
+                        TemplatePart::String(text) => result.push_str(&text),
+                        TemplatePart::Expr(expr) => match self.eval_expr(expr) {
+                            Some(o) if self.is_error(&o) => return Some(o),
+                            Some(o) => result.push_str(&o.to_string()),
+                            None => result.push_str(&Object::Null.to_string()),
                         },
-                        Some(Object::Continue) => continue,
-                        e => _result = e,
                     }
                 }
+                Some(Object::String(result))
+            }
+            ExprKind::Try { body, catch, finally } => Some(self.eval_try_expr(body, catch, finally)),
+            ExprKind::Fun { params, body } => Some(Object::Fn(params, body, self.store.clone(), None)),
+            ExprKind::Call { function, args, optional } => {
+                Some(self.eval_call_expr(*function, args, optional, &span))
+            }
+            ExprKind::Index { array, index, optional } => {
+                let obj = self.eval_expr(*array).unwrap_or(Object::Null);
+                if self.is_error(&obj) || (optional && obj == Object::Null) {
+                    return Some(obj);
+                }
+                let index = self.eval_expr(*index).unwrap_or(Object::Null);
+                if self.is_error(&index) {
+                    return Some(index);
+                }
+                Some(self.eval_index_expr(obj, index, &span))
+            }
+            ExprKind::Member { object, property: Ident(property), optional } => {
+                let object = self.eval_expr(*object)?;
+                if optional && object == Object::Null {
+                    return Some(object);
+                }
+                Some(self.eval_member_expr(object, &property, &span))
+            }
+            ExprKind::Slice { array, start, end, optional } => {
+                let array = self.eval_expr(*array)?;
+                if self.is_error(&array) || (optional && array == Object::Null) {
+                    return Some(array);
+                }
+                let mut bounds = [None, None];
+                for (bound, expr) in bounds.iter_mut().zip([start, end]) {
+                    if let Some(expr) = expr {
+                        match self.eval_expr(*expr)? {
+                            Object::Number(n) => *bound = Some(n),
+                            Object::Null => (),
+                            e @ (Object::Error(..) | Object::Thrown(..)) => return Some(e),
+                            o => return Some(self.error_at(&span, format!("slice index must be a number: {}", o))),
+                        }
+                    }
+                }
+                let slice = self.eval_slice_expr(array, bounds[0], bounds[1]);
+                Some(self.locate(slice, &span))
+            }
+            ExprKind::Range { start, end, inclusive } => {
+                let start = self.eval_expr(*start)?;
+                let end = self.eval_expr(*end)?;
+                Some(match (start, end) {
+                    (Object::Number(start), Object::Number(end)) => Object::Range(start, end, inclusive),
+                    (e, _) | (_, e) if self.is_error(&e) => e,
+                    (start, end) => self.error_at(&span, format!("range bounds must be numbers: {}..{}", start, end)),
+                })
+            }
+            ExprKind::Typeof { expr } => Some(self.eval_typeof_expr(*expr)),
+
+            ExprKind::Loop { body }  => loop {
+                if let Some(done) = self.eval_loop_body(&body) {
+                    return Some(done);
+                }
+            },
+            ExprKind::While { cond, body } => loop {
+                let cond = self.eval_expr((*cond).clone())?;
+                if self.is_error(&cond) {
+                    return Some(cond);
+                }
+                if !self.is_truthy(cond) {
+                    return Some(Object::Null);
+                }
+                if let Some(done) = self.eval_loop_body(&body) {
+                    return Some(done);
+                }
+            },
+            ExprKind::For { key, item, iterable, body } => {
+                let iterable = self.eval_expr(*iterable)?;
+                if self.is_error(&iterable) {
+                    return Some(iterable);
+                }
+                // `for (k in object)` walks the keys, `for (k, v in object)` the entries.
+                let keys_only = key.is_none() && matches!(iterable, Object::Object(_));
+                let entries = match self.iter_entries(iterable) {
+                    Ok(entries) => entries,
+                    Err(msg) => return Some(self.error_at(&span, msg)),
+                };
+                let outer = Rc::clone(&self.store);
+                for (k, v) in entries {
+                    // Each iteration binds the loop variables in its own scope,
+                    // so they never overwrite a binding of the same name outside the loop.
+                    let mut scope = Store::new_enclosed(Rc::clone(&outer));
+                    if let Some(Ident(key)) = &key {
+                        scope.set(key.clone(), k.clone());
+                    }
+                    let v = if keys_only { k } else { v };
+                    scope.set(item.0.clone(), v);
+                    self.store = Rc::new(RefCell::new(scope));
+                    let done = self.eval_loop_body(&body);
+                    self.store = Rc::clone(&outer);
+                    if let Some(done) = done {
+                        return Some(done);
+                    }
+                }
+                Some(Object::Null)
             }
         }
     }
 
-    fn eval_typeof_expr(&mut self, expr: Expr) -> Object {
-        let obj = self.eval_expr(expr);
-        match &obj.unwrap() {
-            Object::Null => Object::String(String::from("null")),
-            Object::Bool(_) => Object::String(String::from("boolean")),
-            Object::Number(_) => Object::String(String::from("number")),
-            Object::String(_) => Object::String(String::from("string")),
-            Object::Array(_) => Object::String(String::from("array")),
-            Object::Object(_) => Object::String(String::from("object")),
-            _ => Object::String(String::from("undefined")),
+    /// ## eval_loop_body
+    /// Runs one iteration of a loop.
+    /// # Arguments
+    /// * `body` - The body of the loop.
+    /// # Returns
+    /// `Option<Object>` - `None` to keep looping,
+    /// or the value the loop ends with: `null` after a `break`,
+    /// or the `return` or error that stopped it.
+    fn eval_loop_body(&mut self, body: &BlockStatement) -> Option<Object> {
+        match self.eval_block_statement(body.to_vec()) {
+            Some(Object::Return(e)) => Some(Object::Return(e)),
+            Some(o @ (Object::Error(..) | Object::Thrown(..))) => Some(o),
+            Some(Object::Break) => Some(Object::Null),
+            _ => None,
+        }
+    }
+
+    /// ## eval_try_expr
+    /// Evaluates `try { } catch (e) { } finally { }`.
+    /// An error or thrown value leaving the body runs the catch block in a new scope,
+    /// with the binding set to the error object built by `error_object`.
+    /// The finally block always runs; a `return`, `break`, `continue` or error in it
+    /// replaces the result of the rest.
+    /// # Arguments
+    /// * `body` - The block after `try`.
+    /// * `catch` - The binding and block of the catch clause.
+    /// * `finally` - The block after `finally`.
+    /// # Returns
+    /// `Object` - The value of the body or of the catch block, `null` if it has none.
+    fn eval_try_expr(
+        &mut self,
+        body: BlockStatement,
+        catch: Option<(Option<Ident>, BlockStatement)>,
+        finally: Option<BlockStatement>,
+    ) -> Object {
+        let mut result = self.eval_block_statement(body);
+        if let Some((binding, handler)) = catch {
+            if let Some(error @ (Object::Error(..) | Object::Thrown(..))) = result {
+                // The handler runs in its own scope, so the binding shadows
+                // an outer variable of the same name instead of overwriting it.
+                let outer = Rc::clone(&self.store);
+                self.store = Rc::new(RefCell::new(Store::new_enclosed(Rc::clone(&outer))));
+                if let Some(Ident(name)) = binding {
+                    self.store.borrow_mut().set(name, error_object(error));
+                }
+                result = self.eval_block_statement(handler);
+                self.store = outer;
+            }
+        }
+        if let Some(finally) = finally {
+            match self.eval_block_statement(finally) {
+                Some(o @ (Object::Return(_) | Object::Break | Object::Continue)) => return o,
+                Some(o) if self.is_error(&o) => return o,
+                _ => (),
+            }
         }
+        result.unwrap_or(Object::Null)
+    }
 
+    /// ## iter_entries
+    /// Lists the `(key, item)` pairs a `for` loop walks over.
+    /// Numbers and ranges are counted lazily, so a loop over `0..1e12` that breaks early
+    /// never builds its entries.
+    /// # Arguments
+    /// * `iterable` - The value after `in`.
+    /// # Returns
+    /// `Result<Box<dyn Iterator<Item = (Object, Object)>>, String>` - For arrays and strings the key is the index,
+    /// for objects the items are the keys and the values,
+    /// and a number `n` counts from `0` up to `n - 1`.
+    fn iter_entries(&mut self, iterable: Object) -> Result<Box<dyn Iterator<Item = (Object, Object)>>, String> {
+        let index = |i: usize| Object::Number(i as f64);
+        Ok(match iterable {
+            Object::Array(items) => Box::new(items.into_iter().enumerate().map(move |(i, item)| (index(i), item))),
+            Object::String(s) => Box::new(
+                s.chars()
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, c)| (index(i), Object::String(c.to_string()))),
+            ),
+            Object::Object(map) => Box::new(map.into_iter()),
+            Object::Number(n) => Box::new(
+                (0..n.max(0.0).ceil() as i64).map(|i| (Object::Number(i as f64), Object::Number(i as f64))),
+            ),
+            Object::Range(start, end, inclusive) => Box::new(
+                (0..)
+                    .map(move |i| (i, start + i as f64))
+                    .take_while(move |&(_, n)| n < end || (inclusive && n == end))
+                    .map(move |(i, n)| (index(i), Object::Number(n))),
+            ),
+            o => return Err(format!("cannot iterate over {}", o)),
+        })
+    }
+
+    fn eval_typeof_expr(&mut self, expr: Expr) -> Object {
+        match self.eval_expr(expr).unwrap_or(Object::Null) {
+            e @ (Object::Error(..) | Object::Thrown(..)) => e,
+            o => Object::String(String::from(o.type_name())),
+        }
     }
 
     /// ## eval_prefix_expr
@@ -370,7 +847,7 @@ impl Eval {
     /// # Returns
     /// `Object` - The result of the evaluation.
     /// # Examples
-    /// ```
+    /// ```text
     /// use crate::eval::Eval;
     /// let eval = Eval::new(Rc::new(RefCell::new(Store::new())));
     /// let expr = Expression::Literal(Literal::Number(1.0));
@@ -381,7 +858,7 @@ impl Eval {
     fn eval_minus_prefix_expr(&mut self, expr: Object) -> Object {
         match expr {
             Object::Number(i) => Object::Number(-i),
-            _ => Object::Error(format!("unknown operator: -{}", expr)),
+            _ => Object::error(format!("unknown operator: -{}", expr)),
         }
     }
 
@@ -396,7 +873,7 @@ impl Eval {
     fn eval_plus_prefix_expr(&mut self, expr: Object) -> Object {
         match expr {
             Object::Number(i) => Object::Number(i),
-            _ => Object::Error(format!("unknown operator: {}", expr)),
+            _ => Object::error(format!("unknown operator: {}", expr)),
         }
     }
 
@@ -413,14 +890,18 @@ impl Eval {
     /// # Errors
     /// `Error` - If the expression is not a number.
     fn eval_infix_expr(&mut self, infix: Infix, left: Object, right: Object) -> Object {
+        // Any value can be compared with `null`.
+        if matches!(infix, Infix::Equals | Infix::NotEquals) && (left == Object::Null || right == Object::Null) {
+            return self.eval_object_infix_expr(infix, left, right);
+        }
         match left {
             Object::Number(left_expr) => {
                 if let Object::Number(right_expr) = right {
                     self.eval_int_infix_expr(infix, left_expr, right_expr)
-                } else if let Object::Object(right_expr) = right {
-                    self.eval_object_infix_expr(infix, Object::Number(left_expr), Object::Object(right_expr))
+                } else if let Object::Object(_) | Object::Array(_) | Object::Range(..) = right {
+                    self.eval_object_infix_expr(infix, Object::Number(left_expr), right)
                 } else {
-                    Object::Error(format!("type mismatch: {} {} {}", left, infix, right))
+                    Object::error(format!("type mismatch: {} {} {}", left, infix, right))
                 }
             }
             Object::String(left_expr) => {
@@ -429,17 +910,55 @@ impl Eval {
                 } else if let Object::Object(right_expr) = right {
                     self.eval_object_infix_expr(infix, Object::String(left_expr), Object::Object(right_expr))
                 } else {
-                    Object::Error(format!("type mismatch: {} {} {}", left_expr, infix, right))
+                    Object::error(format!("type mismatch: {} {} {}", left_expr, infix, right))
                 }
             }
             _ => self.eval_object_infix_expr(infix, left, right)
         }
     }
 
+    /// ## eval_logical_expr
+    /// Evaluates `&&`, `||` and `??`.
+    /// The right operand is only evaluated when the left one does not decide the result,
+    /// so `x != null && x[0]` never indexes a null.
+    /// `a ?? b` is `a` itself unless it is `null`, in which case it is `b`.
+    /// # Arguments
+    /// * `infix` - `Infix::LogicalAnd`, `Infix::LogicalOr` or `Infix::NullCoalesce`.
+    /// * `left` - The left operand.
+    /// * `right` - The right operand.
+    /// # Returns
+    /// `Option<Object>` - A boolean for `&&` and `||`, an operand for `??`,
+    /// or the error raised by an operand.
+    fn eval_logical_expr(&mut self, infix: Infix, left: Expr, right: Expr) -> Option<Object> {
+        let left = self.eval_expr(left)?;
+        if self.is_error(&left) {
+            return Some(left);
+        }
+        if infix == Infix::NullCoalesce {
+            return match left {
+                Object::Null => self.eval_expr(right),
+                left => Some(left),
+            };
+        }
+        let left = self.is_truthy(left);
+        match (infix, left) {
+            (Infix::LogicalAnd, false) => return Some(Object::Bool(false)),
+            (Infix::LogicalOr, true) => return Some(Object::Bool(true)),
+            _ => (),
+        }
+        let right = self.eval_expr(right)?;
+        if self.is_error(&right) {
+            return Some(right);
+        }
+        Some(Object::Bool(self.is_truthy(right)))
+    }
+
     fn eval_string_infix_expr(&mut self, infix: Infix, left: String, right: String) -> Object {
         match infix {
             Infix::Plus => Object::String(format!("{}{}", left, right)),
-            _ => Object::Error(format!("unknown operator: {} {} {}", left, infix, right)),
+            Infix::Equals => Object::Bool(left == right),
+            Infix::NotEquals => Object::Bool(left != right),
+            _ => Object::error(format!("unknown operator: {} {} {}", left, infix, right)),
         }
     }
 
@@ -450,11 +969,15 @@ impl Eval {
                     Object::Bool(right.contains_key(&left))
                 } else if let Object::Array(right) = right {
                     Object::Bool(right.contains(&left))
+                } else if let (Object::Number(n), Object::Range(start, end, inclusive)) = (&left, &right) {
+                    Object::Bool(*n >= *start && (*n < *end || (*inclusive && *n == *end)))
                 } else {
-                    Object::Error(format!("unknown operator: {} {} {}", left, infix, right))
+                    Object::error(format!("unknown operator: {} {} {}", left, infix, right))
                 }
             }
-            _ => Object::Error(format!("unknown operator: {} {} {}", left, infix, right)),
+            Infix::Equals => Object::Bool(left == right),
+            Infix::NotEquals => Object::Bool(left != right),
+            _ => Object::error(format!("unknown operator: {} {} {}", left, infix, right)),
         }
     }
 
@@ -476,28 +999,145 @@ impl Eval {
             Infix::RightShift => Object::Number(((left as i64) >> (right as i64)) as f64),
             Infix::AND => Object::Number((left as i64 & right as i64) as f64),
             Infix::OR => Object::Number((left as i64 | right as i64) as f64),
-            Infix::XOR => Object::Number((left as i64 ^ right as i64) as f64)
+            Infix::XOR => Object::Number((left as i64 ^ right as i64) as f64),
+            _ => Object::error(format!("unknown operator: {} {} {}", left, infix, right)),
         }
     }
 
-    fn eval_call_expr(&mut self, function: Expr, args: Vec<Expr>) -> Object {
-        let args = args
-            .iter()
-            .map(|a| self.eval_expr(a.clone()).unwrap_or(Object::Null))
-            .collect::<Vec<_>>();
+    /// ## eval_call_expr
+    /// Evaluates a call, including method calls, which pass their receiver to `apply_function`.
+    /// Arguments are evaluated left to right; `...array` passes each element of the array,
+    /// and `name: value` is kept apart to fill the parameter with that name.
+    /// An `optional` call, `f?.()`, is `null` when `f` is `null` or a missing field.
+    /// A class method may change `self`; the changed instance is then assigned back
+    /// to the receiver when it is a variable or a field or element of one.
+    fn eval_call_expr(&mut self, function: Expr, args: Vec<Argument>, optional: bool, span: &Span) -> Object {
+        let mut place = None;
+        let (function, mut receiver) = match function.kind {
+            ExprKind::Member { object, property: Ident(name), optional: optional_member } => {
+                // The receiver's place is resolved once, so that `self` can be written back
+                // without evaluating the keys leading to it again.
+                let object = if object.is_assignable() {
+                    let (name, path) = match self.resolve_place(*object, span) {
+                        Ok(place) => place,
+                        Err(e) => return e,
+                    };
+                    let value = self.read_place(&name, &path, span);
+                    place = Some((name, path));
+                    Some(value)
+                } else {
+                    self.eval_expr(*object)
+                };
+                let object = match object {
+                    Some(o) if self.is_error(&o) => return o,
+                    Some(Object::Null) if optional_member => return Object::Null,
+                    Some(o) => o,
+                    None => return Object::Null,
+                };
+                let is_object = matches!(object, Object::Object(_));
+                match self.find_method(object, &name) {
+                    Ok(method) => method,
+                    Err(_) if optional && is_object => return Object::Null,
+                    Err(msg) => return self.error_at(span, msg),
+                }
+            }
+            _ => match self.eval_expr(function) {
+                Some(function) => (function, None),
+                None => return Object::Null,
+            },
+        };
+        if optional && function == Object::Null {
+            return Object::Null;
+        }
 
-        self.apply_function(function, args)
-    }
+        let mut values: Vec<Object> = vec![];
+        let mut named: Vec<(String, Object)> = vec![];
+        for arg in args {
+            let expr = match &arg {
+                Argument::Positional(expr) | Argument::Spread(expr) | Argument::Named(_, expr) => expr.clone(),
+            };
+            let value = match self.eval_expr(expr) {
+                Some(o) if self.is_error(&o) => return o,
+                value => value.unwrap_or(Object::Null),
+            };
+            match (arg, value) {
+                (Argument::Positional(_), value) => values.push(value),
+                (Argument::Spread(_), Object::Array(items)) => values.extend(items),
+                (Argument::Spread(_), o) => {
+                    return self.error_at(span, format!("cannot spread {} {}: only arrays can be spread", o.type_name(), o))
+                }
+                (Argument::Named(Ident(name), _), value) => named.push((name, value)),
+            }
+        }
 
-    fn eval_index_expr(&mut self, left: Object, index: Object) -> Object {
-        match left {
-            Object::Array(ref arr) => {
-                if let Object::Number(i) = index {
-                    self.eval_array_index_expr(arr.clone(), i)
-                } else {
-                    Object::Error(format!("index operator not supported: {}", left))
+        let writes_self = matches!(function, Object::Fn(..)) && receiver.is_some();
+        let result = self.apply_function(function, values, named, receiver.as_mut(), span);
+        if let (true, Some((name, path)), Some(instance)) = (writes_self, place, receiver) {
+            if !self.is_error(&result) {
+                if let Some(e) = self.assign_place(name, &path, instance, span) {
+                    return e;
                 }
             }
+        }
+        result
+    }
+
+    /// ## find_method
+    /// Resolves `object.name` in a call.
+    /// A field of an object holding a function is called as is;
+    /// otherwise the built-in method of the value's type is used.
+    /// # Arguments
+    /// * `object` - The value before the dot.
+    /// * `name` - The name after the dot.
+    /// # Returns
+    /// `Result<(Object, Option<Object>), String>` - The function to call,
+    /// and the receiver to pass as its first argument for built-in methods.
+    fn find_method(&mut self, object: Object, name: &str) -> Result<(Object, Option<Object>), String> {
+        if let Object::Object(hash) = &object {
+            if let Some(field) = hash.get(&Object::String(name.to_string())) {
+                return Ok((field.clone(), None));
+            }
+        }
+        if let Object::Struct(ty, values) = &object {
+            if let Some(i) = ty.field_index(name) {
+                return Ok((values[i].clone(), None));
+            }
+            if let Some(method) = ty.methods.get(name) {
+                return Ok((method.clone(), Some(object)));
+            }
+        }
+        match methods::find_method(&object, name) {
+            Some((method, arity)) => Ok((Object::Inbuilt(method, arity), Some(object))),
+            None => Err(format!("{} has no method {}", object.type_name(), name)),
+        }
+    }
+
+    /// ## eval_member_expr
+    /// Evaluates `object.property` outside of a call.
+    /// Only objects and structs have fields.
+    /// A missing field of an object is `null`, like a missing key,
+    /// while a struct only has the fields its type declares.
+    fn eval_member_expr(&mut self, object: Object, property: &str, span: &Span) -> Object {
+        match object {
+            Object::Object(hash) => hash
+                .get(&Object::String(property.to_string()))
+                .cloned()
+                .unwrap_or(Object::Null),
+            Object::Struct(ty, mut values) => match ty.field_index(property) {
+                Some(i) => values.swap_remove(i),
+                None => self.error_at(span, format!("{} has no field {}", ty.name, property)),
+            },
+            Object::Error(..) | Object::Thrown(..) => object,
+            o => self.error_at(span, format!("{} has no field {}", o.type_name(), property)),
+        }
+    }
+
+    fn eval_index_expr(&mut self, left: Object, index: Object, span: &Span) -> Object {
+        match left {
+            Object::Array(ref arr) => match index {
+                Object::Number(i) => self.eval_array_index_expr(arr.clone(), i),
+                _ => self.error_at(span, format!("cannot index array with {}", index.type_name())),
+            },
             Object::Object(ref hash) => match index {
                 Object::Number(_) | Object::Bool(_) | Object::String(_) => match hash.get(&index) {
                     Some(o) => o.clone(),
@@ -505,10 +1145,48 @@ impl Eval {
                         Object::Null
                     },
                 },
-                Object::Error(_) => index,
-                _ => Object::Error(format!("unsable as hash key: {}", index)),
+                Object::Error(..) | Object::Thrown(..) => index,
+                _ => self.error_at(span, format!("unsable as hash key: {}", index)),
+            },
+            Object::Struct(..) => match index {
+                Object::String(field) => self.eval_member_expr(left, &field, span),
+                Object::Error(..) | Object::Thrown(..) => index,
+                _ => self.error_at(span, format!("struct fields are named by strings: {}", index)),
             },
-            _ => Object::Error(format!("unknown operator: {} {}", left, index)),
+            _ => self.error_at(span, format!("cannot index {}", left.type_name())),
+        }
+    }
+
+    /// ## eval_slice_expr
+    /// Evaluates `array[start:end]` on arrays and strings.
+    /// Negative bounds count from the end, as with indexing,
+    /// and bounds past either end are clamped, so slicing never fails on a valid target.
+    /// # Arguments
+    /// * `array` - The array or string being sliced.
+    /// * `start` - The first index to keep, `0` when left out.
+    /// * `end` - The index to stop before, the length when left out.
+    /// # Returns
+    /// `Object` - The slice, of the same type as `array`.
+    fn eval_slice_expr(&mut self, array: Object, start: Option<f64>, end: Option<f64>) -> Object {
+        let bounds = |len: usize| {
+            let clamp = |i: f64| {
+                let i = if i < 0.0 { len as f64 + i } else { i };
+                i.clamp(0.0, len as f64) as usize
+            };
+            let start = start.map_or(0, clamp);
+            let end = end.map_or(len, clamp);
+            (start, end.max(start))
+        };
+        match array {
+            Object::Array(arr) => {
+                let (start, end) = bounds(arr.len());
+                Object::Array(arr[start..end].to_vec())
+            }
+            Object::String(s) => {
+                let (start, end) = bounds(s.chars().count());
+                Object::String(s.chars().skip(start).take(end - start).collect())
+            }
+            o => Object::error(format!("slice operator not supported: {}", o)),
         }
     }
 
@@ -519,7 +1197,11 @@ impl Eval {
         }
 
         if index < 0.0 {
-            match array.get((array.len() as f64 + index) as usize) {
+            let from_end = max + index;
+            if from_end < 0.0 {
+                return Object::Null;
+            }
+            match array.get(from_end as usize) {
                 Some(o) => return o.clone(),
                 None => return Object::Null,
             }
@@ -530,50 +1212,235 @@ impl Eval {
         }
     }
 
-    fn apply_function(&mut self, function: Expr, args: Vec<Object>) -> Object {
-        let (params, body, store) = match self.eval_expr(function) {
-            Some(Object::Fn(params, body, store)) => (params, body, store),
-            Some(Object::Inbuilt(func)) => return func(args),
-            Some(o) => return Object::Error(format!("function not found: {}", o)),
-            None => return Object::Null,
+    /// ## apply_function
+    /// Calls a user or inbuilt function.
+    /// Both check the number of arguments against their `Arity`;
+    /// user functions then bind each parameter to its positional argument,
+    /// its named argument or its default, in that order,
+    /// and gather the remaining positional arguments into the rest parameter.
+    /// # Arguments
+    /// * `function` - The function to call.
+    /// * `args` - The positional arguments.
+    /// * `named` - The `name: value` arguments.
+    /// * `receiver` - The value a method is called on. Inbuilt methods take it as their
+    ///   first argument; class methods see it as `self`, and it is updated to the value
+    ///   `self` has when the method returns.
+    /// * `span` - The span of the call, for errors.
+    /// # Returns
+    /// `Object` - The value the function returned.
+    fn apply_function(
+        &mut self,
+        function: Object,
+        mut args: Vec<Object>,
+        named: Vec<(String, Object)>,
+        receiver: Option<&mut Object>,
+        span: &Span,
+    ) -> Object {
+        let (params, body, store, name) = match function {
+            Object::Fn(params, body, store, name) => (params, body, store, name),
+            Object::Inbuilt(func, arity) => {
+                let is_method = receiver.is_some();
+                if let Some(receiver) = receiver {
+                    args.insert(0, receiver.clone());
+                }
+                if let Some((name, _)) = named.first() {
+                    return self.error_at(span, format!("inbuilt functions take no named arguments: {}", name));
+                }
+                if let Err(msg) = arity.check(args.len(), is_method) {
+                    return self.error_at(span, msg);
+                }
+                return self.locate(func(args), span);
+            }
+            Object::Constructor(ty) => return self.construct(ty, args, named, span),
+            e @ (Object::Error(..) | Object::Thrown(..)) => return e,
+            o => return self.error_at(span, format!("function not found: {}", o)),
         };
 
-        if params.len() != args.len() {
-            return Object::Error(format!(
-                "expected arguments: {}\ngiven arguments: {}",
-                params.len(),
-                args.len()
-            ));
-        };
+        // Named arguments fill parameters that have no positional argument,
+        // so they count towards the arity too.
+        if let Err(msg) = Arity::of(&params).check(args.len() + named.len(), false) {
+            return self.error_at(span, msg);
+        }
 
+        self.call_stack.push(Frame {
+            name: name.unwrap_or_else(|| String::from("<anonymous>")),
+            span: span.clone(),
+        });
         let current_store = Rc::clone(&self.store);
-        let extended_store = self.extended_function_store(params, store, args);
-        self.store = Rc::new(RefCell::new(extended_store));
-        let evaluated = self.eval_block_statement(body);
+        self.store = Rc::new(RefCell::new(Store::new_enclosed(store)));
+        if let Some(receiver) = &receiver {
+            self.store.borrow_mut().set(String::from("self"), (*receiver).clone());
+        }
+        let evaluated = match self.bind_params(&params, args, named) {
+            Ok(()) => self.eval_block_statement(body),
+            Err(e) => Some(self.locate(e, span)),
+        };
+        if let Some(receiver) = receiver {
+            if let Some(instance) = self.store.borrow_mut().get("self") {
+                *receiver = instance;
+            }
+        }
         self.store = current_store;
+        self.call_stack.pop();
         self.unwrap_return_value(evaluated)
     }
 
-    fn extended_function_store(
+    /// ## construct
+    /// Calls the constructor of a struct type.
+    /// Fields are filled like the parameters of a function,
+    /// so `Point(1, 2)` and `Point(y: 2, x: 1)` build the same value.
+    /// A class with an `init` method is built by calling `init` instead,
+    /// on an instance whose fields all start as `null`.
+    fn construct(&mut self, ty: Rc<StructType>, args: Vec<Object>, named: Vec<(String, Object)>, span: &Span) -> Object {
+        if let Some(init) = ty.methods.get("init").cloned() {
+            let mut instance = Object::Struct(Rc::clone(&ty), vec![Object::Null; ty.fields.len()]);
+            let result = self.apply_function(init, args, named, Some(&mut instance), span);
+            if self.is_error(&result) {
+                return result;
+            }
+            return instance;
+        }
+        let params = Params {
+            positional: ty.fields.iter().map(|field| Pattern::Binding(Ident(field.clone()))).collect(),
+            rest: None,
+        };
+        if let Err(msg) = Arity::of(&params).check(args.len() + named.len(), false) {
+            return self.error_at(span, msg);
+        }
+        let current_store = Rc::clone(&self.store);
+        self.store = Rc::new(RefCell::new(Store::new()));
+        let bound = self.bind_params(&params, args, named);
+        let fields = std::mem::replace(&mut self.store, current_store);
+        if let Err(e) = bound {
+            return self.locate(e, span);
+        }
+        let values = ty
+            .fields
+            .iter()
+            .map(|field| fields.borrow_mut().get(field).unwrap_or(Object::Null))
+            .collect();
+        Object::Struct(ty, values)
+    }
+
+    /// Binds the parameters of a function in the current store, which is the call's scope.
+    /// Parameters are bound in order, so a default can refer to the parameters before it.
+    fn bind_params(
         &mut self,
-        params: Vec<Ident>,
-        store: Rc<RefCell<Store>>,
+        params: &Params,
         args: Vec<Object>,
-    ) -> Store {
-        let mut scope_store = Store::new_enclosed(store);
+        mut named: Vec<(String, Object)>,
+    ) -> Result<(), Object> {
+        let names: Vec<Option<&str>> = params.positional.iter().map(param_name).collect();
+        if let Some((name, _)) = named.iter().find(|(n, _)| !names.contains(&Some(n.as_str()))) {
+            return Err(Object::error(format!("unknown named argument: {}", name)));
+        }
+        let mut args = args.into_iter();
+        for (param, name) in params.positional.iter().zip(names) {
+            let by_name = named.iter().position(|(n, _)| Some(n.as_str()) == name);
+            let value = match (args.next(), by_name) {
+                (Some(_), Some(_)) => {
+                    return Err(Object::error(format!("argument {} given both by position and by name", name.unwrap_or_default())))
+                }
+                (Some(value), None) => value,
+                (None, Some(i)) => named.remove(i).1,
+                (None, None) => self.pattern_default(param, || format!("missing argument for {}", param))?,
+            };
+            let mut bindings = vec![];
+            self.destructure(param, value, &mut bindings)?;
+            for (name, value) in bindings {
+                self.store.borrow_mut().set(name, value);
+            }
+        }
+        if let Some((name, _)) = named.first() {
+            return Err(Object::error(format!("argument {} given more than once", name)));
+        }
+        if let Some(Ident(rest)) = &params.rest {
+            self.store.borrow_mut().set(rest.clone(), Object::Array(args.collect()));
+        }
+        Ok(())
+    }
 
-        for (ident, arg) in params.iter().zip(args.iter()) {
-            let Ident(name) = ident.clone();
-            scope_store.set(name, arg.to_owned());
+    /// ## destructure
+    /// Binds the parts of `value` to the names in `pattern`, as in `set [a, b] = pair;`.
+    /// Unlike `match`, a value that does not fit is an error rather than a failed test.
+    /// Extra array elements and object keys are ignored;
+    /// missing ones take the pattern's default, if it has one.
+    /// # Arguments
+    /// * `pattern` - The pattern to destructure with.
+    /// * `value` - The value to take apart.
+    /// * `bindings` - Collects `(name, value)` pairs.
+    /// # Returns
+    /// `Result<(), Object>` - An error describing the part that did not fit.
+    #[allow(clippy::mutable_key_type)]
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: Object,
+        bindings: &mut Vec<(String, Object)>,
+    ) -> Result<(), Object> {
+        match pattern {
+            Pattern::Default(pattern, _) => self.destructure(pattern, value, bindings),
+            Pattern::Array { items, rest } => {
+                let mut array = match value {
+                    Object::Array(array) => array,
+                    o => return Err(Object::error(format!("cannot destructure {} {} with {}", o.type_name(), o, pattern))),
+                };
+                let len = array.len();
+                let rest_value = array.split_off(items.len().min(len));
+                for (i, item) in items.iter().enumerate() {
+                    let element = match array.get_mut(i) {
+                        Some(element) => std::mem::replace(element, Object::Null),
+                        None => self.pattern_default(item, || {
+                            format!("missing element {} in {}: the array has {} elements", i, pattern, len)
+                        })?,
+                    };
+                    self.destructure(item, element, bindings)?;
+                }
+                match rest {
+                    Some(rest) => self.destructure(rest, Object::Array(rest_value), bindings),
+                    None => Ok(()),
+                }
+            }
+            Pattern::Object(entries) => {
+                let mut hash = match value {
+                    Object::Object(hash) => hash,
+                    o => return Err(Object::error(format!("cannot destructure {} {} with {}", o.type_name(), o, pattern))),
+                };
+                for (key, item) in entries {
+                    let field = match hash.remove(&Object::String(key.clone())) {
+                        Some(field) => field,
+                        None => self.pattern_default(item, || format!("missing key {:?} in {}", key, pattern))?,
+                    };
+                    self.destructure(item, field, bindings)?;
+                }
+                Ok(())
+            }
+            _ => {
+                if pattern::match_pattern(pattern, &value, bindings) {
+                    Ok(())
+                } else {
+                    Err(Object::error(format!("{} does not match {}", value, pattern)))
+                }
+            }
         }
+    }
 
-        scope_store
+    /// Evaluates the default of a pattern whose value is missing,
+    /// or returns the error built by `missing` when it has none.
+    fn pattern_default(&mut self, pattern: &Pattern, missing: impl Fn() -> String) -> Result<Object, Object> {
+        match pattern {
+            Pattern::Default(_, default) => match self.eval_expr((**default).clone()) {
+                Some(e @ (Object::Error(..) | Object::Thrown(..))) => Err(e),
+                value => Ok(value.unwrap_or(Object::Null)),
+            },
+            _ => Err(Object::error(missing())),
+        }
     }
 
     fn extend_global_store(&mut self, lib: String) -> Option<Object> {
         let lib_store = match load_etrl(lib.clone()) {
             Some(e) => e,
-            None => return Some(Object::Error(format!("Could not load lib: {}", lib))),
+            None => return Some(Object::error(format!("Could not load lib: {}", lib))),
         };
         let mut new_store = Store::new_enclosed(self.store.clone());
         for (k, v) in lib_store {
@@ -591,7 +1458,7 @@ impl Eval {
         }
     }
 
-    fn eval_ident(&mut self, ident: Ident) -> Object {
+    fn eval_ident(&mut self, ident: Ident, span: &Span) -> Object {
         let Ident(i) = ident;
         let builtins =  new_globals();
         if builtins.contains_key(&i) {
@@ -599,7 +1466,7 @@ impl Eval {
         };
         match self.store.borrow_mut().get(&i) {
             Some(i) => i,
-            None => Object::Error(format!("identifier not found: {}", i)),
+            None => self.error_at(span, format!("identifier not found: {}", i)),
         }
     }
 
@@ -616,6 +1483,7 @@ impl Eval {
             Literal::String(s) => Object::String(s),
             Literal::Number(i) => Object::Number(i),
             Literal::Boolean(b) => Object::Bool(b),
+            Literal::Null => Object::Null,
             Literal::Array(a) => Object::Array(
                 a.iter()
                     .map(|e| self.eval_expr(e.clone()).unwrap_or(Object::Null))
@@ -629,13 +1497,16 @@ impl Eval {
     /// { "a": 1, "b": 2 }
     /// => { "a": 1, "b": 2 }
     /// # Examples
-    /// ```
+    /// ```text
     /// use etrl::{Evaluator, Literal, Ident};
     /// let mut eval = Evaluator::new();
     /// let obj = Literal::Object(vec![(Ident::new("a"), Literal::Number(1)), (Ident::new("b"), Literal::Number(2))]);
     /// let result = eval.eval_object_literal(obj);
     /// assert_eq!(result, Object::Object(vec![(Ident::new("a"), Object::Number(1)), (Ident::new("b"), Object::Number(2))]));
     /// ```
+    // Objects are hashed by value; the interior mutability in `Object` never
+    // takes part in `Hash` or `Eq`.
+    #[allow(clippy::mutable_key_type)]
     fn eval_object_literal(&mut self, h: Vec<(Expr, Expr)>) -> Object {
         let mut hash = HashMap::new();
 
@@ -654,4 +1525,48 @@ impl Eval {
         }
         Object::Object(hash)
     }
-}
\ No newline at end of file
+}
+
+/// The name a parameter can be passed by, if it is a plain name.
+fn param_name(param: &Pattern) -> Option<&str> {
+    match param {
+        Pattern::Binding(Ident(name)) => Some(name),
+        Pattern::Default(pattern, _) => param_name(pattern),
+        _ => None,
+    }
+}
+
+/// ## error_object
+/// Builds the object a `catch` binds, with `message`, `kind`, `location` and `stack` fields.
+/// The location is where the error was raised or thrown, such as `"main.etrl:3:5"`,
+/// and the stack is an array of strings such as `"inner (main.etrl:3:5)"`, innermost call first.
+/// Runtime errors have the kind `"runtime"`.
+/// A thrown object keeps its own fields, and gets those four when it lacks them;
+/// any other thrown value becomes the message, with the kind `"thrown"`.
+#[allow(clippy::mutable_key_type)]
+fn error_object(error: Object) -> Object {
+    let field = |name: &str| Object::String(name.to_string());
+    let stack = |frames: Vec<Frame>| Object::Array(frames.iter().map(|frame| Object::String(frame.to_string())).collect());
+    let mut fields = HashMap::new();
+    let kind = match error {
+        Object::Error(message, location, frames) => {
+            let location = location.map_or(Object::Null, |span| Object::String(span.to_string()));
+            fields.insert(field("message"), Object::String(message));
+            fields.insert(field("location"), location);
+            fields.insert(field("stack"), stack(frames));
+            "runtime"
+        }
+        Object::Thrown(value, span, frames) => {
+            fields.insert(field("message"), Object::String(value.to_string()));
+            fields.insert(field("location"), Object::String(span.to_string()));
+            fields.insert(field("stack"), stack(frames));
+            if let Object::Object(hash) = *value {
+                fields.extend(hash);
+            }
+            "thrown"
+        }
+        o => return o,
+    };
+    fields.entry(field("kind")).or_insert(Object::String(kind.to_string()));
+    Object::Object(fields)
+}
@@ -1,5 +1,5 @@
 use super::store::Store;
-use crate::ast::{BlockStatement, Ident};
+use crate::ast::{span::Span, BlockStatement, Params, Pattern};
 use std::{
     cell::RefCell,
     collections::HashMap,
@@ -10,6 +10,120 @@ use std::{
 
 pub type InbuiltFunction = fn(Vec<Object>) -> Object;
 
+/// ## Frame
+/// A call on the evaluator's call stack:
+/// the name of the function and the span of the call.
+/// Functions bound with `set` are named after the binding, others are `<anonymous>`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Frame {
+    pub name: String,
+    pub span: Span,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.span)
+    }
+}
+
+/// ## render_trace
+/// Renders a stack as the lines following an error message:
+/// ```text
+///     at inner (main.etrl:3:5)
+///     at outer (main.etrl:7:1)
+/// ```
+/// # Arguments
+/// * `frames` - The frames, innermost first.
+/// # Returns
+/// `String` - One line per frame, each starting with a newline; empty for an empty stack.
+pub fn render_trace(frames: &[Frame]) -> String {
+    frames.iter().map(|frame| format!("\n    at {}", frame)).collect()
+}
+
+/// ## StructType
+/// A type declared with `struct Point { x, y }`: its name and its fields, in order.
+/// Types declared with `class` also carry their methods, which are `Fn`s called with the instance bound to `self`.
+#[derive(PartialEq, Debug)]
+pub struct StructType {
+    pub name: String,
+    pub fields: Vec<String>,
+    pub methods: HashMap<String, Object>,
+}
+
+impl StructType {
+    /// The position of `field` in the values of a struct of this type.
+    pub fn field_index(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|f| f == field)
+    }
+}
+
+/// ## Arity
+/// How many arguments a function accepts:
+/// at least `min`, and at most `max` unless it is variadic.
+/// Calls to inbuilt and user functions are both checked against it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Arity {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    pub fn exactly(n: usize) -> Self {
+        Arity { min: n, max: Some(n) }
+    }
+
+    pub fn between(min: usize, max: usize) -> Self {
+        Arity { min, max: Some(max) }
+    }
+
+    pub fn at_least(min: usize) -> Self {
+        Arity { min, max: None }
+    }
+
+    /// The arity of a user function.
+    /// Parameters up to the last one without a default are required,
+    /// and a rest parameter lifts the upper bound.
+    pub fn of(params: &Params) -> Self {
+        let min = params
+            .positional
+            .iter()
+            .rposition(|p| !matches!(p, Pattern::Default(..)))
+            .map_or(0, |i| i + 1);
+        match params.rest {
+            Some(_) => Arity::at_least(min),
+            None => Arity::between(min, params.positional.len()),
+        }
+    }
+
+    /// Checks the number of arguments given to a call.
+    /// # Arguments
+    /// * `given` - The number of arguments, including the receiver of a method.
+    /// * `receiver` - Whether the call is a method call, whose receiver is passed
+    ///   as the first argument but is left out of the error message.
+    /// # Returns
+    /// `Result<(), String>` - An error naming the expected and given counts.
+    pub fn check(&self, given: usize, receiver: bool) -> Result<(), String> {
+        let within_max = match self.max {
+            Some(max) => given <= max,
+            None => true,
+        };
+        if given >= self.min && within_max {
+            return Ok(());
+        }
+        let skip = receiver as usize;
+        let expected = match self.max {
+            Some(max) if max == self.min => format!("{}", max - skip),
+            Some(max) => format!("{} to {}", self.min - skip, max - skip),
+            None => format!("at least {}", self.min - skip),
+        };
+        Err(format!(
+            "wrong number of arguments: expected {}, got {}",
+            expected,
+            given - skip
+        ))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Object {
     Number(f64),
@@ -17,11 +131,25 @@ pub enum Object {
     Bool(bool),
     Null,
     Return(Box<Object>),
-    Error(String),
-    Fn(Vec<Ident>, BlockStatement, Rc<RefCell<Store>>),
-    Inbuilt(InbuiltFunction),
+    /// A runtime error: its message, where it was raised and the stack it was raised in,
+    /// innermost call first.
+    /// Inbuilt functions raise errors without a location; the evaluator adds the span of the call.
+    Error(String, Option<Span>, Vec<Frame>),
+    /// A value raised by `throw`, with the span of the `throw` and the stack it was thrown in.
+    /// Like an error it stops evaluation until a `catch` handles it.
+    Thrown(Box<Object>, Span, Vec<Frame>),
+    /// A user function: its parameters, body, closure,
+    /// and the name it was bound to with `set`, if any.
+    Fn(Params, BlockStatement, Rc<RefCell<Store>>, Option<String>),
+    Inbuilt(InbuiltFunction, Arity),
     Array(Vec<Object>),
     Object(HashMap<Object, Object>),
+    /// The constructor of a struct type, called as `Point(1, 2)` or `Point(x: 1, y: 2)`.
+    Constructor(Rc<StructType>),
+    /// A value of a struct type, with one value per field in the order they were declared.
+    Struct(Rc<StructType>, Vec<Object>),
+    /// `start..end`, or `start..=end` when the flag is set.
+    Range(f64, f64, bool),
     Typeof(Box<Object>),
     Loop(Box<BlockStatement>),
     Break,
@@ -36,11 +164,15 @@ impl PartialEq for Object {
             (Object::Bool(a), Object::Bool(b)) => a == b,
             (Object::Null, Object::Null) => true,
             (Object::Return(a), Object::Return(b)) => a == b,
-            (Object::Error(a), Object::Error(b)) => a == b,
-            (Object::Fn(a, b, c), Object::Fn(d, e, f)) => a == d && b == e && c == f,
-            (Object::Inbuilt(a), Object::Inbuilt(b)) => a == b,
+            (Object::Error(a, b, _), Object::Error(c, d, _)) => a == c && b == d,
+            (Object::Thrown(a, ..), Object::Thrown(b, ..)) => a == b,
+            (Object::Fn(a, b, c, _), Object::Fn(d, e, f, _)) => a == d && b == e && c == f,
+            (Object::Inbuilt(a, _), Object::Inbuilt(b, _)) => *a as usize == *b as usize,
             (Object::Array(a), Object::Array(b)) => a == b,
             (Object::Object(a), Object::Object(b)) => a == b,
+            (Object::Constructor(a), Object::Constructor(b)) => Rc::ptr_eq(a, b),
+            (Object::Struct(a, b), Object::Struct(c, d)) => Rc::ptr_eq(a, c) && b == d,
+            (Object::Range(a, b, c), Object::Range(d, e, f)) => a == d && b == e && c == f,
             (Object::Typeof(a), Object::Typeof(b)) => a == b,
             _ => false,
         }
@@ -49,6 +181,29 @@ impl PartialEq for Object {
 
 impl Eq for Object {}
 
+impl Object {
+    /// An error without a location, as raised by inbuilt functions.
+    pub fn error(message: impl Into<String>) -> Object {
+        Object::Error(message.into(), None, vec![])
+    }
+
+    /// The name `typeof` reports for this value.
+    /// A struct reports the name of its type.
+    pub fn type_name(&self) -> &str {
+        match self {
+            Object::Null => "null",
+            Object::Bool(_) => "boolean",
+            Object::Number(_) => "number",
+            Object::String(_) => "string",
+            Object::Array(_) => "array",
+            Object::Object(_) => "object",
+            Object::Range(..) => "range",
+            Object::Struct(ty, _) => &ty.name,
+            _ => "undefined",
+        }
+    }
+}
+
 impl fmt::Display for Object {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -57,19 +212,31 @@ impl fmt::Display for Object {
             Object::Bool(ref value) => write!(f, "{}", value),
             Object::Null => write!(f, "null"),
             Object::Return(ref value) => write!(f, "{}", value),
-            Object::Error(ref value) => write!(f, "{}", value),
-            Object::Fn(ref params, _, _) => {
-                let mut result = String::new();
-                for (i, Ident(ref s)) in params.iter().enumerate() {
-                    if i < 1 {
-                        result.push_str(s);
-                    } else {
-                        result.push_str(&format!(", {}", s));
-                    }
+            Object::Error(ref message, ref location, ref frames) => match location {
+                Some(span) => write!(f, "{}: {}{}", span, message, render_trace(frames)),
+                None => write!(f, "{}{}", message, render_trace(frames)),
+            },
+            Object::Thrown(ref value, _, ref frames) => {
+                write!(f, "uncaught exception: {}{}", value, render_trace(frames))
+            }
+            Object::Fn(ref params, ..) => write!(f, "fn({}) {{ ... }}", params),
+            Object::Inbuilt(..) => write!(f, "[inbuilt fn]"),
+            Object::Constructor(ref ty) => {
+                let keyword = if ty.methods.is_empty() { "struct" } else { "class" };
+                write!(f, "{} {} {{ {} }}", keyword, ty.name, ty.fields.join(", "))
+            }
+            Object::Struct(ref ty, ref values) => {
+                let fields: Vec<String> = ty
+                    .fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| format!("{}: {}", field, value))
+                    .collect();
+                match fields.is_empty() {
+                    true => write!(f, "{} {{}}", ty.name),
+                    false => write!(f, "{} {{ {} }}", ty.name, fields.join(", ")),
                 }
-                write!(f, "fn({}) {{ ... }}", result)
             }
-            Object::Inbuilt(_) => write!(f, "[inbuilt fn]"),
             Object::Array(ref val) => {
                 let mut result = String::new();
                 for (i, obj) in val.iter().enumerate() {
@@ -93,6 +260,9 @@ impl fmt::Display for Object {
 
                 write!(f, "{{{}}}", res)
             }
+            Object::Range(start, end, inclusive) => {
+                write!(f, "{}{}{}", start, if inclusive { "..=" } else { ".." }, end)
+            }
             Object::Typeof(ref obj) => write!(f, "typeof({})", obj),
             Object::Loop(ref _block) => write!(f, "loop {{ ... }}"),
             Object::Break => write!(f, "break"),
@@ -0,0 +1,73 @@
+use super::object::Object;
+use crate::ast::{Ident, Literal, Pattern};
+
+/// ## match_pattern
+/// Tests `value` against `pattern`.
+/// The names the pattern binds are pushed onto `bindings`;
+/// callers should only apply them when the whole pattern matched.
+/// # Arguments
+/// * `pattern` - The pattern to test.
+/// * `value` - The value being matched.
+/// * `bindings` - Collects `(name, value)` pairs.
+/// # Returns
+/// `bool` - Whether the value matched.
+#[allow(clippy::mutable_key_type)]
+pub fn match_pattern(pattern: &Pattern, value: &Object, bindings: &mut Vec<(String, Object)>) -> bool {
+    match pattern {
+        Pattern::Wildcard => true,
+        Pattern::Binding(Ident(name)) => {
+            bindings.push((name.clone(), value.clone()));
+            true
+        }
+        Pattern::Literal(lit) => match (lit, value) {
+            (Literal::Number(a), Object::Number(b)) => a == b,
+            (Literal::String(a), Object::String(b)) => a == b,
+            (Literal::Boolean(a), Object::Bool(b)) => a == b,
+            (Literal::Null, Object::Null) => true,
+            _ => false,
+        },
+        Pattern::Range(start, end, inclusive) => match value {
+            Object::Number(n) => n >= start && (n < end || (*inclusive && n == end)),
+            _ => false,
+        },
+        Pattern::Array { items, rest } => {
+            let array = match value {
+                Object::Array(array) => array,
+                _ => return false,
+            };
+            let length_ok = match rest {
+                Some(_) => array.len() >= items.len(),
+                None => array.len() == items.len(),
+            };
+            if !length_ok {
+                return false;
+            }
+            for (item, value) in items.iter().zip(array) {
+                if !match_pattern(item, value, bindings) {
+                    return false;
+                }
+            }
+            match rest {
+                Some(rest) => {
+                    let rest_value = Object::Array(array[items.len()..].to_vec());
+                    match_pattern(rest, &rest_value, bindings)
+                }
+                None => true,
+            }
+        }
+        Pattern::Object(entries) => {
+            let hash = match value {
+                Object::Object(hash) => hash,
+                _ => return false,
+            };
+            entries.iter().all(|(key, pattern)| {
+                match hash.get(&Object::String(key.clone())) {
+                    Some(value) => match_pattern(pattern, value, bindings),
+                    None => false,
+                }
+            })
+        }
+        Pattern::Typeof(name) => value.type_name() == name,
+        Pattern::Default(pattern, _) => match_pattern(pattern, value, bindings),
+    }
+}
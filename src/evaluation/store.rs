@@ -60,7 +60,7 @@ impl Store {
         }
     }
 
-    pub fn iter (&self) -> std::collections::hash_map::Iter<String, Object> {
+    pub fn iter (&self) -> std::collections::hash_map::Iter<'_, String, Object> {
         self.store.iter()
     }
 }
@@ -1,181 +1,679 @@
-use crate::ast::token::Token;
-use std::collections::HashMap;
+use crate::{
+    ast::{span::Span, token::{Token, TokenKind}, Infix},
+    diagnostic::{self, Diagnostic},
+};
+use std::{collections::HashMap, rc::Rc};
+use unicode_xid::UnicodeXID;
 
 lazy_static::lazy_static! {
-    static ref KEYWORDS: HashMap<&'static str, Token> = {
+    static ref KEYWORDS: HashMap<&'static str, TokenKind> = {
         let mut keywords = HashMap::new();
-        keywords.insert("set", Token::Set);
-        keywords.insert("anew", Token::Anew);
-        keywords.insert("fun", Token::Func);
-        keywords.insert("if", Token::If);
-        keywords.insert("else", Token::Else);
-        keywords.insert("return", Token::Return);
-        keywords.insert("include", Token::Include);
-        keywords.insert("true", Token::Boolean(true));
-        keywords.insert("false", Token::Boolean(false));
-        keywords.insert("typeof", Token::Typeof);
-        keywords.insert("loop", Token::Loop);
-        keywords.insert("break", Token::Break);
-        keywords.insert("continue", Token::Continue);
+        keywords.insert("set", TokenKind::Set);
+        keywords.insert("anew", TokenKind::Anew);
+        keywords.insert("fun", TokenKind::Func);
+        keywords.insert("if", TokenKind::If);
+        keywords.insert("else", TokenKind::Else);
+        keywords.insert("return", TokenKind::Return);
+        keywords.insert("include", TokenKind::Include);
+        keywords.insert("true", TokenKind::Boolean(true));
+        keywords.insert("false", TokenKind::Boolean(false));
+        keywords.insert("null", TokenKind::Null);
+        keywords.insert("typeof", TokenKind::Typeof);
+        keywords.insert("loop", TokenKind::Loop);
+        keywords.insert("while", TokenKind::While);
+        keywords.insert("match", TokenKind::Match);
+        keywords.insert("for", TokenKind::For);
+        keywords.insert("in", TokenKind::In);
+        keywords.insert("break", TokenKind::Break);
+        keywords.insert("continue", TokenKind::Continue);
+        keywords.insert("throw", TokenKind::Throw);
+        keywords.insert("try", TokenKind::Try);
+        keywords.insert("catch", TokenKind::Catch);
+        keywords.insert("finally", TokenKind::Finally);
+        keywords.insert("struct", TokenKind::Struct);
+        keywords.insert("class", TokenKind::Class);
         keywords
     };
 }
 
-fn is_letter(c: char) -> bool {
-    c.is_ascii_alphabetic() || c == '_'
+/// Identifiers follow the Unicode `XID_Start`/`XID_Continue` rules,
+/// with `_` also allowed as the first character.
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_xid_start()
 }
 
-pub fn find_indentifier(ident: &str) -> Option<&Token> {
+fn is_ident_continue(c: char) -> bool {
+    c.is_xid_continue()
+}
+
+/// `_` may only appear between digits.
+fn check_separators(parts: &[String]) -> Result<(), String> {
+    if parts.iter().any(|p| p.starts_with('_') || p.ends_with('_')) {
+        return Err(String::from("`_` must be placed between digits"));
+    }
+    Ok(())
+}
+
+pub fn find_indentifier(ident: &str) -> Option<&TokenKind> {
     KEYWORDS.get(ident)
 }
 
+/// ## Lexer
+/// Turns source text into tokens.
+/// The lexer walks the input once with a byte cursor:
+/// `position` is the byte offset of `ch` and `read_position` the offset of the character after it,
+/// so slicing the input and building spans is always done on character boundaries.
 pub struct Lexer {
     input: String,
+    file: Rc<str>,
     position: usize,
     read_position: usize,
     ch: char,
+    line: usize,
+    column: usize,
+    /// One entry per `${` of a template literal being lexed,
+    /// counting the braces opened inside it so the `}` that closes it can be told apart.
+    template_braces: Vec<usize>,
+    pub errors: Vec<Diagnostic>,
 }
 
 impl Lexer {
     pub fn new(input: String) -> Lexer {
-        Lexer {
-            ch: input.chars().next().unwrap(),
+        Lexer::new_with_file(input, "")
+    }
+
+    /// Creates a lexer whose spans point into `file`.
+    pub fn new_with_file(input: String, file: &str) -> Lexer {
+        let mut lexer = Lexer {
+            ch: '\0',
             input,
+            file: Rc::from(file),
             position: 0,
-            read_position: 1,
-        }
+            read_position: 0,
+            line: 1,
+            column: 0,
+            template_braces: vec![],
+            errors: vec![],
+        };
+        lexer.read_char();
+        lexer
     }
 
     pub fn read_char(&mut self) {
-        self.ch = if self.read_position >= self.input.len() {
-            '\0'
+        if self.ch == '\n' {
+            self.line += 1;
+            self.column = 1;
         } else {
-            self.input.chars().nth(self.read_position).unwrap()
-        };
+            self.column += 1;
+        }
 
         self.position = self.read_position;
-        self.read_position += 1;
+        self.ch = match self.input[self.read_position..].chars().next() {
+            Some(c) => {
+                self.read_position += c.len_utf8();
+                c
+            }
+            None => '\0',
+        };
+    }
+
+    /// Whether the cursor has moved past the last character.
+    fn at_end(&self) -> bool {
+        self.position >= self.input.len()
     }
 
     fn read_identifier(&mut self) -> String {
         let pos: usize = self.position;
-        while is_letter(self.ch) {
+        self.read_char();
+        while is_ident_continue(self.ch) {
             self.read_char()
         }
         self.input[pos..self.position].to_string()
     }
 
+    /// Reads a number literal.
+    /// Besides plain decimals (`42`, `3.14`) this accepts exponents (`1e9`, `2.5E-3`),
+    /// `0x`, `0b` and `0o` prefixed integers, and `_` separators between digits (`1_000_000`).
+    /// A malformed literal is reported as an error and read as `0`.
     fn read_number(&mut self) -> f64 {
+        let (start, line, column) = (self.position, self.line, self.column);
+        let radix = match (self.ch, self.peek_char()) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'o' | 'O') => 8,
+            ('0', 'b' | 'B') => 2,
+            _ => 10,
+        };
+
+        let value = if radix == 10 {
+            self.read_decimal()
+        } else {
+            self.read_char();
+            self.read_char();
+            self.read_radix(radix)
+        };
+
+        // Anything glued to the end of the literal, such as the `.3` in `1.2.3`
+        // or the `px` in `10px`, makes the whole literal malformed.
+        let value = if (self.ch == '.' && self.peek_char().is_ascii_digit()) || is_ident_continue(self.ch) {
+            while self.ch == '.' || is_ident_continue(self.ch) {
+                self.read_char();
+            }
+            Err(String::from("unexpected characters after the number"))
+        } else {
+            value
+        };
+
+        match value {
+            Ok(n) => n,
+            Err(label) => {
+                let span = self.span_from(start, line, column);
+                let msg = format!("Malformed number literal `{}`", &self.input[span.start..span.end]);
+                self.errors.push(
+                    Diagnostic::error(diagnostic::INVALID_NUMBER, msg, span).with_primary_label(&label),
+                );
+                0.0
+            }
+        }
+    }
+
+    /// Reads the digits and `_` separators under the cursor.
+    /// Letters are read too, so that they can be reported as invalid digits.
+    fn read_digits(&mut self, letters: bool) -> String {
         let pos: usize = self.position;
-        while self.ch.is_numeric() || self.ch == '.' {
+        while self.ch.is_ascii_digit() || self.ch == '_' || (letters && self.ch.is_ascii_alphabetic()) {
+            self.read_char();
+        }
+        self.input[pos..self.position].to_string()
+    }
+
+    fn read_decimal(&mut self) -> Result<f64, String> {
+        let mut text = self.read_digits(false);
+        let mut parts = vec![text.clone()];
+        if self.ch == '.' && self.peek_char().is_ascii_digit() {
+            self.read_char();
+            let fraction = self.read_digits(false);
+            text.push('.');
+            text.push_str(&fraction);
+            parts.push(fraction);
+        }
+
+        if self.ch == 'e' || self.ch == 'E' {
+            let sign = self.peek_char() == '+' || self.peek_char() == '-';
+            let digit = if sign { self.peek_nth(2) } else { self.peek_char() };
+            if !digit.is_ascii_digit() {
+                self.read_char();
+                return Err(String::from("expected digits after the exponent"));
+            }
             self.read_char();
+            text.push('e');
+            if sign {
+                text.push(self.ch);
+                self.read_char();
+            }
+            let exponent = self.read_digits(false);
+            text.push_str(&exponent);
+            parts.push(exponent);
+        }
+
+        check_separators(&parts)?;
+        text.replace('_', "")
+            .parse::<f64>()
+            .map_err(|e| e.to_string())
+    }
+
+    fn read_radix(&mut self, radix: u32) -> Result<f64, String> {
+        let name = match radix {
+            16 => "hexadecimal",
+            8 => "octal",
+            _ => "binary",
+        };
+        let digits = self.read_digits(true);
+        if digits.is_empty() {
+            return Err(format!("expected {} digits", name));
         }
-        self.input[pos..self.position].parse::<f64>().unwrap()
+        if let Some(c) = digits.chars().find(|c| *c != '_' && !c.is_digit(radix)) {
+            return Err(format!("invalid digit `{}` in {} literal", c, name));
+        }
+        check_separators(std::slice::from_ref(&digits))?;
+        u64::from_str_radix(&digits.replace('_', ""), radix)
+            .map(|n| n as f64)
+            .map_err(|_| format!("{} literal is too large", name))
     }
 
+    /// Reads a string literal, starting at its opening quote.
+    /// A string opened with `"""` may span several lines,
+    /// and a newline right after the opening quotes is dropped.
+    /// Escape sequences are processed in both forms.
     fn read_string(&mut self) -> String {
-        let pos: usize = self.position + 1;
+        let (start, line, column) = (self.position, self.line, self.column);
+        let triple = self.peek_char() == '"' && self.peek_nth(2) == '"';
+        if triple {
+            self.read_char();
+            self.read_char();
+            if self.peek_char() == '\r' && self.peek_nth(2) == '\n' {
+                self.read_char();
+            }
+            if self.peek_char() == '\n' {
+                self.read_char();
+            }
+        }
+
+        let mut value = String::new();
         loop {
             self.read_char();
-            if self.ch == '"' || self.ch == (0 as char) {
-                break;
+            match self.ch {
+                '\0' if self.at_end() => {
+                    self.unterminated_string(start, line, column);
+                    break;
+                }
+                '"' if !triple => break,
+                '"' if self.peek_char() == '"' && self.peek_nth(2) == '"' => {
+                    self.read_char();
+                    self.read_char();
+                    break;
+                }
+                '\\' => self.read_escape(&mut value),
+                c => value.push(c),
+            }
+        }
+        value
+    }
+
+    /// Reads a piece of a template literal such as `` `Hello ${name}!` ``,
+    /// starting at the opening backtick, or at the `}` closing an embedded expression
+    /// when `opening` is false.
+    /// A piece ending in `${` is followed by the tokens of the embedded expression.
+    /// A template without any `${` is an ordinary string.
+    fn read_template(&mut self, opening: bool) -> TokenKind {
+        let (start, line, column) = (self.position, self.line, self.column);
+        let mut value = String::new();
+        loop {
+            self.read_char();
+            match self.ch {
+                '\0' if self.at_end() => {
+                    self.unterminated_string(start, line, column);
+                    break;
+                }
+                '`' => break,
+                '$' if self.peek_char() == '{' => {
+                    self.read_char();
+                    self.template_braces.push(0);
+                    return match opening {
+                        true => TokenKind::TemplateStart(value),
+                        false => TokenKind::TemplateMiddle(value),
+                    };
+                }
+                '\\' => self.read_escape(&mut value),
+                c => value.push(c),
+            }
+        }
+        match opening {
+            true => TokenKind::String(value),
+            false => TokenKind::TemplateEnd(value),
+        }
+    }
+
+    /// Reads a raw string such as `r"C:\path"` or `r#"say "hi""#`, starting at the `r`.
+    /// Escapes are not processed, and the string ends at a quote
+    /// followed by as many `#` as it was opened with.
+    fn read_raw_string(&mut self) -> String {
+        let (start, line, column) = (self.position, self.line, self.column);
+        self.read_char();
+        let mut hashes = 0;
+        while self.ch == '#' {
+            hashes += 1;
+            self.read_char();
+        }
+
+        let mut value = String::new();
+        loop {
+            self.read_char();
+            match self.ch {
+                '\0' if self.at_end() => {
+                    self.unterminated_string(start, line, column);
+                    break;
+                }
+                '"' if (1..=hashes).all(|i| self.peek_nth(i) == '#') => {
+                    for _ in 0..hashes {
+                        self.read_char();
+                    }
+                    break;
+                }
+                c => value.push(c),
+            }
+        }
+        value
+    }
+
+    /// Whether the `r` under the cursor opens a raw string.
+    fn is_raw_string_start(&self) -> bool {
+        let mut i = 1;
+        while self.peek_nth(i) == '#' {
+            i += 1;
+        }
+        self.peek_nth(i) == '"'
+    }
+
+    /// Reads the escape sequence starting at the backslash under the cursor
+    /// and pushes the character it stands for onto `value`.
+    fn read_escape(&mut self, value: &mut String) {
+        let (start, line, column) = (self.position, self.line, self.column);
+        self.read_char();
+        let escaped = match self.ch {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '`' => '`',
+            '$' => '$',
+            'u' => return self.read_unicode_escape(value, start, line, column),
+            '\0' if self.at_end() => return,
+            c => {
+                let mut span = self.span_from(start, line, column);
+                span.end += 1;
+                self.errors.push(
+                    Diagnostic::error(
+                        diagnostic::INVALID_ESCAPE,
+                        format!("Unknown escape sequence \\{}", c),
+                        span,
+                    )
+                    .with_primary_label("unknown escape")
+                    .with_note("supported escapes are \\n \\t \\r \\0 \\\\ \\\" \\' \\` \\$ and \\u{...}"),
+                );
+                value.push('\\');
+                c
+            }
+        };
+        value.push(escaped);
+    }
+
+    /// Reads a `\u{...}` escape, with the cursor on the `u`.
+    fn read_unicode_escape(&mut self, value: &mut String, start: usize, line: usize, column: usize) {
+        let mut digits = String::new();
+        let mut closed = false;
+        if self.peek_char() == '{' {
+            self.read_char();
+            while self.peek_char().is_ascii_hexdigit() && digits.len() < 6 {
+                self.read_char();
+                digits.push(self.ch);
+            }
+            if self.peek_char() == '}' {
+                self.read_char();
+                closed = true;
+            }
+        }
+
+        match u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32) {
+            Some(c) if closed => value.push(c),
+            _ => {
+                let mut span = self.span_from(start, line, column);
+                span.end += 1;
+                self.errors.push(
+                    Diagnostic::error(
+                        diagnostic::INVALID_ESCAPE,
+                        String::from("Invalid unicode escape"),
+                        span,
+                    )
+                    .with_primary_label("expected \\u{...} with 1 to 6 hex digits of a valid code point"),
+                );
+                value.push(char::REPLACEMENT_CHARACTER);
             }
         }
-        self.input[pos..self.position].to_string()
     }
 
+    fn unterminated_string(&mut self, start: usize, line: usize, column: usize) {
+        let span = self.span_from(start, line, column);
+        self.errors.push(
+            Diagnostic::error(
+                diagnostic::UNTERMINATED_STRING,
+                String::from("Unterminated string literal"),
+                span,
+            )
+            .with_primary_label("string starts here and is never closed"),
+        );
+    }
+
+    fn span_from(&self, start: usize, line: usize, column: usize) -> Span {
+        Span::new(self.file.clone(), line, column, start, self.position)
+    }
+
+    /// Reads a `//` comment up to, but not including, the end of the line.
     pub fn read_comment(&mut self) -> String {
         let pos: usize = self.position;
         loop {
             self.read_char();
-            if self.ch == '\n' || self.ch == (0 as char) {
+            if self.ch == '\n' || self.at_end() {
                 break;
             }
         }
         self.input[pos..self.position].to_string()
     }
 
+    /// Reads a `/* ... */` comment, starting at its `/`.
+    /// Block comments nest, so `/* a /* b */ c */` is a single comment.
+    pub fn read_block_comment(&mut self) -> String {
+        let (start, line, column) = (self.position, self.line, self.column);
+        self.read_char();
+        let mut depth = 1;
+        loop {
+            self.read_char();
+            if self.at_end() {
+                let span = self.span_from(start, line, column);
+                self.errors.push(
+                    Diagnostic::error(
+                        diagnostic::UNTERMINATED_COMMENT,
+                        String::from("Unterminated block comment"),
+                        span,
+                    )
+                    .with_primary_label("comment starts here and is never closed"),
+                );
+                return self.input[start..].to_string();
+            }
+            if self.ch == '/' && self.peek_char() == '*' {
+                self.read_char();
+                depth += 1;
+            } else if self.ch == '*' && self.peek_char() == '/' {
+                self.read_char();
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+        self.input[start..self.read_position].to_string()
+    }
+
     fn skip_whitespace(&mut self) {
-        while self.ch == ' ' || self.ch == '\t' || self.ch == '\n' || self.ch == '\r' {
+        while self.ch.is_whitespace() {
             self.read_char()
         }
     }
 
     fn peek_char(&self) -> char {
-        if self.read_position >= self.input.len() {
-            return 0 as char;
+        self.input[self.read_position..].chars().next().unwrap_or('\0')
+    }
+
+    /// Looks `n` characters ahead of the cursor.
+    fn peek_nth(&self, n: usize) -> char {
+        self.input[self.position..].chars().nth(n).unwrap_or('\0')
+    }
+
+    /// Reads the `=` of a compound assignment such as `+=`, if there is one.
+    /// # Arguments
+    /// * `op` - The operator the assignment applies.
+    /// * `plain` - The token to produce when no `=` follows.
+    fn compound(&mut self, op: Infix, plain: TokenKind) -> TokenKind {
+        if self.peek_char() == '=' {
+            self.read_char();
+            TokenKind::CompoundAssign(op)
+        } else {
+            plain
         }
-        self.input.chars().nth(self.read_position).unwrap()
     }
 
     pub fn next_token(&mut self) -> Token {
         self.skip_whitespace();
-        let tok: Token = match self.ch {
+        let (start, line, column) = (self.position, self.line, self.column);
+        let kind = self.read_token();
+        Token::new(kind, self.span_from(start, line, column))
+    }
+
+    fn read_token(&mut self) -> TokenKind {
+        let tok: TokenKind = match self.ch {
             '=' => {
-                if self.peek_char() == '=' {
+                match self.peek_char() {
+                    '=' => {
+                        self.read_char();
+                        TokenKind::Equals
+                    }
+                    '>' => {
+                        self.read_char();
+                        TokenKind::FatArrow
+                    }
+                    _ => TokenKind::Assign,
+                }
+            }
+            ';' => TokenKind::Semicolon,
+            ':' => TokenKind::Colon,
+            '?' => match self.peek_char() {
+                '.' => {
+                    self.read_char();
+                    TokenKind::QuestionDot
+                }
+                '?' => {
+                    self.read_char();
+                    TokenKind::NullCoalesce
+                }
+                _ => TokenKind::Question,
+            },
+            ',' => TokenKind::Comma,
+            '+' if self.peek_char() == '+' => {
+                self.read_char();
+                TokenKind::Increment
+            }
+            '-' if self.peek_char() == '-' => {
+                self.read_char();
+                TokenKind::Decrement
+            }
+            '+' => self.compound(Infix::Plus, TokenKind::Plus),
+            '-' => self.compound(Infix::Minus, TokenKind::Minus),
+            '&' => {
+                if self.peek_char() == '&' {
+                    self.read_char();
+                    TokenKind::LogicalAnd
+                } else {
+                    self.compound(Infix::AND, TokenKind::AND)
+                }
+            }
+            '|' => {
+                if self.peek_char() == '|' {
                     self.read_char();
-                    Token::Equals
+                    TokenKind::LogicalOr
                 } else {
-                    Token::Assign
-                }
-            }
-            ';' => Token::Semicolon,
-            ':' => Token::Colon,
-            ',' => Token::Comma,
-            '+' => Token::Plus,
-            '-' => Token::Minus,
-            '&' => Token::AND,
-            '|' => Token::OR,
-            '^' => Token::XOR,
-            '%' => Token::Percent,
+                    self.compound(Infix::OR, TokenKind::OR)
+                }
+            }
+            '^' => self.compound(Infix::XOR, TokenKind::XOR),
+            '%' => self.compound(Infix::Modulo, TokenKind::Percent),
             '!' => {
                 if self.peek_char() == '=' {
                     self.read_char();
-                    Token::NotEquals
+                    TokenKind::NotEquals
                 } else {
-                    Token::Bang
-                }
-            }
-            '~' => Token::In,
-            '(' => Token::LeftParen,
-            ')' => Token::RightParen,
-            '{' => Token::LeftBrace,
-            '}' => Token::RightBrace,
-            '[' => Token::LeftBracket,
-            ']' => Token::RightBracket,
-            '*' => Token::Asterisk,
-            '/' => {
-                if self.peek_char() == '/' {
-                    self.read_comment();
-                    Token::Comment
-                } else {
-                    Token::Slash
+                    TokenKind::Bang
+                }
+            }
+            '~' => TokenKind::In,
+            '.' if self.peek_char() == '.' => {
+                self.read_char();
+                match self.peek_char() {
+                    '=' => {
+                        self.read_char();
+                        TokenKind::DotDotEqual
+                    }
+                    '.' => {
+                        self.read_char();
+                        TokenKind::Ellipsis
+                    }
+                    _ => TokenKind::DotDot,
+                }
+            }
+            '.' => TokenKind::Dot,
+            '(' => TokenKind::LeftParen,
+            ')' => TokenKind::RightParen,
+            '{' => {
+                if let Some(braces) = self.template_braces.last_mut() {
+                    *braces += 1;
                 }
+                TokenKind::LeftBrace
             }
+            '}' => match self.template_braces.last_mut() {
+                Some(0) => {
+                    self.template_braces.pop();
+                    self.read_template(false)
+                }
+                Some(braces) => {
+                    *braces -= 1;
+                    TokenKind::RightBrace
+                }
+                None => TokenKind::RightBrace,
+            },
+            '`' => self.read_template(true),
+            '[' => TokenKind::LeftBracket,
+            ']' => TokenKind::RightBracket,
+            '*' => self.compound(Infix::Times, TokenKind::Asterisk),
+            '/' => match self.peek_char() {
+                '/' if self.peek_nth(2) == '/' && self.peek_nth(3) != '/' => {
+                    let comment = self.read_comment();
+                    let doc = comment.trim_start_matches('/');
+                    let doc = doc.strip_prefix(' ').unwrap_or(doc);
+                    return TokenKind::DocComment(doc.trim_end().to_string());
+                }
+                '/' => return TokenKind::Comment(self.read_comment()),
+                '*' => TokenKind::Comment(self.read_block_comment()),
+                _ => self.compound(Infix::Divide, TokenKind::Slash),
+            },
             '<' => match self.peek_char() {
-                    '<' => Token::LeftShift,
-                    '=' => Token::LessEqual,
-                    _ => Token::Less
-                },
+                '<' => {
+                    self.read_char();
+                    self.compound(Infix::LeftShift, TokenKind::LeftShift)
+                }
+                '=' => {
+                    self.read_char();
+                    TokenKind::LessEqual
+                }
+                _ => TokenKind::Less,
+            },
             '>' => match self.peek_char() {
-                    '>' => Token::RightShift,
-                    '=' => Token::GreaterEqual,
-                    _ => Token::Greater
-                },
-            '"' => Token::String(self.read_string()),
-            '\u{0}' => Token::Eof,
+                '>' => {
+                    self.read_char();
+                    self.compound(Infix::RightShift, TokenKind::RightShift)
+                }
+                '=' => {
+                    self.read_char();
+                    TokenKind::GreaterEqual
+                }
+                _ => TokenKind::Greater,
+            },
+            '"' => TokenKind::String(self.read_string()),
+            '\u{0}' if self.at_end() => return TokenKind::Eof,
             _ => {
-                if is_letter(self.ch) {
+                if self.ch == 'r' && self.is_raw_string_start() {
+                    TokenKind::String(self.read_raw_string())
+                } else if is_ident_start(self.ch) {
                     let i: String = self.read_identifier();
                     return match find_indentifier(i.as_str()) {
                         Some(a) => a.to_owned(),
-                        _ => Token::Ident(i),
+                        _ => TokenKind::Ident(i),
                     };
-                } else if self.ch.is_numeric() {
+                } else if self.ch.is_ascii_digit() {
                     let i: f64 = self.read_number();
-                    return Token::Number(i);
+                    return TokenKind::Number(i);
                 } else {
-                    Token::Illegal
+                    TokenKind::Illegal
                 }
             }
         };
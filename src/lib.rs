@@ -1,4 +1,5 @@
 pub mod ast;
+pub mod diagnostic;
 pub mod evaluation;
 pub mod lexer;
 pub mod parser;
@@ -6,22 +7,24 @@ pub mod std_library;
 pub mod repl;
 use std::{rc::Rc, cell::RefCell};
 
+use diagnostic::render_diagnostics;
 use evaluation::{object::*, store::*, *};
 use lexer::Lexer;
 use parser::Parser;
 
 pub fn interpret(content: &str) {
+    interpret_file("", content)
+}
+
+/// Runs `content`, reporting errors against `file`.
+pub fn interpret_file(file: &str, content: &str) {
     let store = Store::new();
-    let mut evaluator = Eval {
-        store: Rc::new(RefCell::new(store)),
-    };
-    let lexer = Lexer::new(content.to_string());
+    let mut evaluator = Eval::new(Rc::new(RefCell::new(store)));
+    let lexer = Lexer::new_with_file(content.to_string(), file);
     let mut parser = Parser::new(lexer);
     let program = parser.parse_program();
     if !parser.errors.is_empty() {
-        for e in parser.errors.iter() {
-            println!("\t{}", e);
-        }
+        println!("{}", render_diagnostics(&parser.errors, content));
         return;
     }
     let res = evaluator.eval(program);
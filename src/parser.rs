@@ -1,10 +1,18 @@
-use crate::{ast::{*, token::Token}, lexer::Lexer};
+use crate::{
+    ast::{*, span::Span, token::{Token, TokenKind}},
+    diagnostic::{self, Diagnostic},
+    lexer::Lexer,
+};
 
 pub struct Parser {
     lexer: Lexer,
     current_token: Token,
     peek_token: Token,
-    pub errors: Vec<String>
+    /// Doc comments written right before `current_token` / `peek_token`.
+    current_doc: Option<String>,
+    peek_doc: Option<String>,
+    pub comments: Vec<Comment>,
+    pub errors: Vec<Diagnostic>
 }
 
 impl Parser {
@@ -12,8 +20,11 @@ impl Parser {
     pub fn new(lexer: Lexer) -> Self {
         let mut p: Parser = Parser {
             lexer,
-            current_token: Token::Eof,
-            peek_token: Token::Eof,
+            current_token: Token::new(TokenKind::Eof, Span::default()),
+            peek_token: Token::new(TokenKind::Eof, Span::default()),
+            current_doc: None,
+            peek_doc: None,
+            comments: vec![],
             errors: vec![],
         };
         p.next_token();
@@ -23,29 +34,102 @@ impl Parser {
 
     pub fn next_token(&mut self) {
         self.current_token = self.peek_token.clone();
+        self.current_doc = self.peek_doc.take();
         self.peek_token = self.lexer.next_token();
+        loop {
+            let (text, doc) = match &self.peek_token.kind {
+                TokenKind::Comment(text) => (text.clone(), false),
+                TokenKind::DocComment(text) => (text.clone(), true),
+                _ => break,
+            };
+            if doc {
+                self.peek_doc = Some(match self.peek_doc.take() {
+                    Some(prev) => format!("{}\n{}", prev, text),
+                    None => text.clone(),
+                });
+            }
+            self.comments.push(Comment { text, doc, span: self.peek_token.span.clone() });
+            self.peek_token = self.lexer.next_token();
+        }
+        self.errors.append(&mut self.lexer.errors);
     }
 
+    /// ## parse_program
+    /// Parses the whole input.
+    /// A statement that fails to parse is reported in `errors` and skipped,
+    /// so the returned program holds every statement that did parse
+    /// and can still be used by tooling.
     pub fn parse_program(&mut self) -> Program {
         let mut statements: Vec<Statement> = vec![];
-        while self.current_token != Token::Eof {
-            let stmt: Option<Statement> = self.parse_statement();
-            if stmt != None {
-                statements.push(stmt.unwrap());
+        while self.current_token.kind != TokenKind::Eof {
+            match self.parse_statement() {
+                Some(stmt) => statements.push(stmt),
+                None => {
+                    self.synchronize();
+                    // At the top level a `}` can only close the construct that failed,
+                    // such as a `match` or object literal, so it is skipped as well.
+                    while self.peek_token(&TokenKind::RightBrace) {
+                        self.next_token();
+                    }
+                }
             };
             self.next_token();
         }
-        Program { statements }
+        Program {
+            statements,
+            comments: std::mem::take(&mut self.comments),
+        }
+    }
+
+    /// ## synchronize
+    /// Skips tokens after a parse error until a statement boundary:
+    /// a `;`, a `}` or a statement keyword.
+    /// Blocks opened while skipping are skipped as a whole,
+    /// so this keeps one bad statement from producing a cascade of errors.
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        loop {
+            match self.current_token.kind {
+                TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightBrace if depth > 0 => depth -= 1,
+                TokenKind::Semicolon if depth == 0 => return,
+                TokenKind::RightBrace | TokenKind::Eof => return,
+                _ => (),
+            }
+            match self.peek_token.kind {
+                TokenKind::Eof => return,
+                TokenKind::RightBrace
+                | TokenKind::Set
+                | TokenKind::Func
+                | TokenKind::If
+                | TokenKind::Loop
+                | TokenKind::While
+                | TokenKind::For
+                | TokenKind::Match
+                | TokenKind::Return
+                | TokenKind::Include
+                | TokenKind::Anew
+                | TokenKind::Throw
+                | TokenKind::Try
+                | TokenKind::Struct
+                | TokenKind::Class if depth == 0 => return,
+                _ => self.next_token(),
+            }
+        }
     }
 
     pub fn parse_statement(&mut self) -> Option<Statement> {
-        match self.current_token {
-            Token::Set => self.parse_set_statement(),
-            Token::Return => self.parse_return_statement(),
-            Token::Include => self.parse_include_statement(),
-            Token::Anew => self.parse_anew_expr(),
-            Token::Break => self.parse_break_statement(),
-            Token::Continue => self.parse_continue_statement(),
+        match self.current_token.kind {
+            TokenKind::Semicolon => None,
+            TokenKind::Set => self.parse_set_statement(),
+            TokenKind::Return => self.parse_return_statement(),
+            TokenKind::Include => self.parse_include_statement(),
+            TokenKind::Anew => self.parse_anew_expr(),
+            TokenKind::Break => self.parse_break_statement(),
+            TokenKind::Continue => self.parse_continue_statement(),
+            TokenKind::Throw => self.parse_throw_statement(),
+            TokenKind::Struct => self.parse_struct_statement(),
+            TokenKind::Class => self.parse_class_statement(),
             _ => self.parse_expr_statement(),
         }
     }
@@ -53,86 +137,121 @@ impl Parser {
 
     pub fn parse_expr_statement(&mut self) -> Option<Statement> {
         match self.parse_expr(Precedence::Lowest) {
+            Some(target) if self.peek_assignment() => {
+                self.next_token();
+                self.parse_assignment(target.span.clone(), target)
+            }
             Some(expression) => {
-                if self.peek_token(&Token::Semicolon) {
+                let span = expression.span.clone();
+                if self.peek_token(&TokenKind::Semicolon) {
                     self.next_token();
                 }
-                Some(Statement::Expression(expression))
+                Some(Statement::new(StatementKind::Expression(expression), span))
             }
             None => None,
         }
     }
 
     pub fn parse_set_statement(&mut self) -> Option<Statement> {
-        match &self.peek_token {
-            Token::Ident(_) => self.next_token(),
+        let start = self.current_token.span.clone();
+        let doc = self.current_doc.take();
+        match &self.peek_token.kind {
+            TokenKind::Ident(_) => self.next_token(),
+            TokenKind::LeftBracket | TokenKind::LeftBrace => {
+                self.next_token();
+                return self.parse_destructure_statement(start);
+            }
             _ => {
-                self.peek_error(Token::Ident(String::new()));
+                self.peek_error(TokenKind::Ident(String::new()));
                 return None;
             }
         }
 
         let name: Ident = match self.parse_ident() {
-            Some(Expr::Ident(ref mut s)) => s.clone(),
+            Some(Expr { kind: ExprKind::Ident(s), .. }) => s,
             _ => return None,
         };
 
-        if !self.expect_peek(Token::Assign) {
+        if !self.expect_peek(TokenKind::Assign) {
             return None;
         }
 
         self.next_token();
 
-        let lit: Expr = match self.parse_expr(Precedence::Lowest) {
-            Some(e) => e,
-            None => return None,
-        };
+        let lit: Expr = self.parse_expr(Precedence::Lowest)?;
 
-        while !self.current_token(Token::Semicolon) {
-            self.next_token();
-        }
+        self.skip_semicolon();
+
+        let mut statement = Statement::new(StatementKind::Set(name, lit), start.to(&self.current_token.span));
+        statement.doc = doc;
+        Some(statement)
+    }
 
-        Some(Statement::Set(name, lit))
+    /// ## parse_destructure_statement
+    /// Parses `set [a, b] = value;` and `set { "key": k } = value;`,
+    /// with the current token on the opening bracket or brace.
+    fn parse_destructure_statement(&mut self, start: Span) -> Option<Statement> {
+        let pattern = self.parse_pattern()?;
+        if !self.expect_peek(TokenKind::Assign) {
+            return None;
+        }
+        self.next_token();
+        let lit: Expr = self.parse_expr(Precedence::Lowest)?;
+        self.skip_semicolon();
+        Some(Statement::new(StatementKind::Destructure(pattern, lit), start.to(&self.current_token.span)))
     }
 
     pub fn parse_return_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token.span.clone();
+        if self.peek_token(&TokenKind::Semicolon) || self.peek_token(&TokenKind::RightBrace) {
+            self.skip_semicolon();
+            return Some(Statement::new(StatementKind::Return(None), start.to(&self.current_token.span)));
+        }
         self.next_token();
 
-        let exp = match self.parse_expr(Precedence::Lowest) {
-            Some(e) => e,
-            None => return None,
-        };
+        let exp = Some(self.parse_expr(Precedence::Lowest)?);
 
-        while !self.current_token(Token::Semicolon) {
-            self.next_token();
-        }
+        self.skip_semicolon();
+
+        Some(Statement::new(StatementKind::Return(exp), start.to(&self.current_token.span)))
+    }
 
-        Some(Statement::Return(exp))
+    pub fn parse_throw_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token.span.clone();
+        self.next_token();
+        let value = self.parse_expr(Precedence::Lowest)?;
+        self.skip_semicolon();
+        Some(Statement::new(StatementKind::Throw(value), start.to(&self.current_token.span)))
     }
 
     pub fn parse_include_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token.span.clone();
         self.next_token();
         // the string next to the include keyword is the lib
-        let lib = match &self.current_token {
-            Token::String(ref s) => s.clone(),
+        let lib = match &self.current_token.kind {
+            TokenKind::String(ref s) => s.clone(),
             _ => {
-                self.peek_error(Token::String(String::new()));
+                self.peek_error(TokenKind::String(String::new()));
                 return None;
             } 
         };
-        while !self.current_token(Token::Semicolon) {
-            self.next_token();
-        }
-        Some(Statement::Include(lib))
+        self.skip_semicolon();
+        Some(Statement::new(StatementKind::Include(lib), start.to(&self.current_token.span)))
     }
 
     fn parse_block_statement(&mut self) -> BlockStatement {
         self.next_token();
 
         let mut statements = vec![];
-        while !self.current_token(Token::RightBrace) && !self.current_token(Token::Eof) {
-            if let Some(s) = self.parse_statement() {
-                statements.push(s);
+        while !self.current_token(TokenKind::RightBrace) && !self.current_token(TokenKind::Eof) {
+            match self.parse_statement() {
+                Some(s) => statements.push(s),
+                None => {
+                    self.synchronize();
+                    if self.current_token(TokenKind::RightBrace) {
+                        break;
+                    }
+                }
             }
             self.next_token();
         }
@@ -141,193 +260,655 @@ impl Parser {
     }
 
     pub fn parse_anew_expr(&mut self) -> Option<Statement> {
-        match &self.peek_token {
-            Token::Ident(_) => self.next_token(),
+        let start = self.current_token.span.clone();
+        match &self.peek_token.kind {
+            TokenKind::Ident(_) => self.next_token(),
             _ => {
-                self.peek_error(Token::Ident(String::new()));
+                self.peek_error(TokenKind::Ident(String::new()));
                 return None;
             }
         }
 
-        let name: Ident = match self.parse_ident() {
-            Some(Expr::Ident(ref mut s)) => s.clone(),
-            _ => return None,
-        };
+        let target = self.parse_expr(Precedence::Lowest)?;
+        if !self.peek_assignment() {
+            self.peek_error(TokenKind::Assign);
+            return None;
+        }
+        self.next_token();
+        self.parse_assignment(start, target)
+    }
+
+    fn peek_assignment(&self) -> bool {
+        matches!(
+            self.peek_token.kind,
+            TokenKind::Assign
+                | TokenKind::CompoundAssign(_)
+                | TokenKind::Increment
+                | TokenKind::Decrement
+        )
+    }
 
-        if !self.expect_peek(Token::Assign) {
+    /// ## parse_assignment
+    /// Parses the rest of an assignment, with the current token on its operator.
+    /// The target must be a variable or an index or field inside one,
+    /// such as `cfg["db"]["port"]` or `cfg.db.port`.
+    /// `x += v` and `x++` become a compound assignment that updates the target in place,
+    /// with `x++` and `x--` adding or subtracting `1`.
+    fn parse_assignment(&mut self, start: Span, target: Expr) -> Option<Statement> {
+        if !target.is_assignable() {
+            let msg = String::from("Invalid assignment target");
+            self.errors.push(
+                Diagnostic::error(diagnostic::INVALID_ASSIGNMENT, msg, target.span.clone())
+                    .with_primary_label("cannot assign to this expression"),
+            );
             return None;
         }
 
-        self.next_token();
+        let op = match &self.current_token.kind {
+            TokenKind::CompoundAssign(op) => Some(op.clone()),
+            TokenKind::Increment => Some(Infix::Plus),
+            TokenKind::Decrement => Some(Infix::Minus),
+            _ => None,
+        };
 
-        let lit: Expr = match self.parse_expr(Precedence::Lowest) {
-            Some(e) => e,
-            None => return None,
+        let lit: Expr = match self.current_token.kind {
+            TokenKind::Increment | TokenKind::Decrement => Expr::new(
+                ExprKind::Literal(Literal::Number(1.0)),
+                self.current_token.span.clone(),
+            ),
+            _ => {
+                self.next_token();
+                self.parse_expr(Precedence::Lowest)?
+            }
         };
 
-        while !self.current_token(Token::Semicolon) {
-            self.next_token();
-        }
+        self.skip_semicolon();
 
-        Some(Statement::Anew(name, lit))
+        let kind = match op {
+            Some(op) => StatementKind::CompoundAssign(target, op, lit),
+            None => StatementKind::Anew(target, lit),
+        };
+        Some(Statement::new(kind, start.to(&self.current_token.span)))
     }
 
     pub fn parse_break_statement(&mut self) -> Option<Statement> {
-        self.next_token();
-        while !self.current_token(Token::Semicolon) {
-            self.next_token();
-        }
-        Some(Statement::Break)
+        let start = self.current_token.span.clone();
+        self.skip_semicolon();
+        Some(Statement::new(StatementKind::Break, start.to(&self.current_token.span)))
     }
 
     pub fn parse_continue_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token.span.clone();
+        self.skip_semicolon();
+        Some(Statement::new(StatementKind::Continue, start.to(&self.current_token.span)))
+    }
+
+    /// ## parse_struct_statement
+    /// Parses `struct Point { x, y }`. A trailing comma is allowed after the last field.
+    pub fn parse_struct_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token.span.clone();
+        let name = self.parse_loop_variable()?;
+        if !self.expect_peek(TokenKind::LeftBrace) {
+            return None;
+        }
+        let mut fields = vec![];
+        while !self.peek_token(&TokenKind::RightBrace) {
+            fields.push(self.parse_loop_variable()?);
+            if !self.peek_token(&TokenKind::RightBrace) && !self.expect_peek(TokenKind::Comma) {
+                return None;
+            }
+        }
         self.next_token();
-        while !self.current_token(Token::Semicolon) {
-            self.next_token();
+        self.skip_semicolon();
+        Some(Statement::new(StatementKind::Struct(name, fields), start.to(&self.current_token.span)))
+    }
+
+    /// ## parse_class_statement
+    /// Parses a class: fields like a struct's, followed or interleaved with methods.
+    /// ```text
+    /// class Counter {
+    ///     count,
+    ///     fun init(start) { self.count = start; }
+    ///     fun inc() { self.count += 1; }
+    /// }
+    /// ```
+    /// Methods are written like named functions and are not separated by commas.
+    pub fn parse_class_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token.span.clone();
+        let name = self.parse_loop_variable()?;
+        if !self.expect_peek(TokenKind::LeftBrace) {
+            return None;
         }
-        Some(Statement::Continue)
+        let mut fields = vec![];
+        let mut methods = vec![];
+        while !self.peek_token(&TokenKind::RightBrace) {
+            if self.peek_token(&TokenKind::Func) {
+                self.next_token();
+                let method = self.parse_loop_variable()?;
+                if !self.expect_peek(TokenKind::LeftParen) {
+                    return None;
+                }
+                let params = self.parse_params()?;
+                if !self.expect_peek(TokenKind::LeftBrace) {
+                    return None;
+                }
+                methods.push((method, params, self.parse_block_statement()));
+                continue;
+            }
+            fields.push(self.parse_loop_variable()?);
+            if !self.peek_token(&TokenKind::RightBrace)
+                && !self.peek_token(&TokenKind::Func)
+                && !self.expect_peek(TokenKind::Comma)
+            {
+                return None;
+            }
+        }
+        self.next_token();
+        self.skip_semicolon();
+        Some(Statement::new(
+            StatementKind::Class(name, fields, methods),
+            start.to(&self.current_token.span),
+        ))
     }
 
     fn parse_typof_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
         self.next_token();
-        let expr = match self.parse_expr(Precedence::Lowest) {
-            Some(e) => e,
-            None => return None,
-        };
-        Some(Expr::Typeof { expr: Box::new(expr) })
+        let expr = self.parse_expr(Precedence::Lowest)?;
+        let span = start.to(&expr.span);
+        Some(Expr::new(ExprKind::Typeof { expr: Box::new(expr) }, span))
     }
 
     fn parse_loop_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
         self.next_token();
         let body = self.parse_block_statement();
-        Some(Expr::Loop { body })
+        Some(Expr::new(ExprKind::Loop { body }, start.to(&self.current_token.span)))
     }
 
-    fn parse_expr(&mut self, precedence: Precedence) -> Option<Expr> {
-        let mut left: Option<Expr> = match self.current_token {
-            Token::Ident(_) => self.parse_ident(),
-            Token::Bang | Token::Minus | Token::Plus => self.parse_prefix_expr(),
-            Token::LeftParen => self.parse_grouped_expr(),
-            Token::If => self.parse_if_expr(),
-            Token::Func => self.parse_fn_expr(),
-            Token::Number(_) => self.parse_int_literal(),
-            Token::Boolean(_) => self.parse_boolean_literal(),
-            Token::String(_) => self.parse_string_literal(),
-            Token::LeftBracket => self.parse_array_literal(),
-            Token::LeftBrace => self.parse_object_literal(),
-            Token::Typeof => self.parse_typof_expr(),
-            Token::Loop => self.parse_loop_expr(),
+    fn parse_while_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
+        if !self.expect_peek(TokenKind::LeftParen) {
+            return None;
+        }
+        self.next_token();
+        let cond = self.parse_expr(Precedence::Lowest)?;
+        if !self.expect_peek(TokenKind::RightParen) || !self.expect_peek(TokenKind::LeftBrace) {
+            return None;
+        }
+        let body = self.parse_block_statement();
+        Some(Expr::new(
+            ExprKind::While { cond: Box::new(cond), body },
+            start.to(&self.current_token.span),
+        ))
+    }
+
+    /// ## parse_match_expr
+    /// Parses `match (value) { pattern => body, ... }`.
+    /// An arm's body is an expression, or a block when it starts with `{`,
+    /// so an object literal body has to be wrapped in parentheses.
+    /// Commas between arms are optional after a block.
+    fn parse_match_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
+        if !self.expect_peek(TokenKind::LeftParen) {
+            return None;
+        }
+        self.next_token();
+        let value = self.parse_expr(Precedence::Lowest)?;
+        if !self.expect_peek(TokenKind::RightParen) || !self.expect_peek(TokenKind::LeftBrace) {
+            return None;
+        }
+
+        let mut arms = vec![];
+        while !self.peek_token(&TokenKind::RightBrace) {
+            self.next_token();
+            let pattern = self.parse_pattern()?;
+            let mut guard = None;
+            if self.peek_token(&TokenKind::If) {
+                self.next_token();
+                self.next_token();
+                guard = Some(self.parse_expr(Precedence::Lowest)?);
+            }
+            if !self.expect_peek(TokenKind::FatArrow) {
+                return None;
+            }
+            self.next_token();
+            let body = if self.current_token(TokenKind::LeftBrace) {
+                let body = self.parse_block_statement();
+                if self.peek_token(&TokenKind::Comma) {
+                    self.next_token();
+                }
+                body
+            } else {
+                let expr = self.parse_expr(Precedence::Lowest)?;
+                if !self.peek_token(&TokenKind::RightBrace) && !self.expect_peek(TokenKind::Comma) {
+                    return None;
+                }
+                let span = expr.span.clone();
+                vec![Statement::new(StatementKind::Expression(expr), span)]
+            };
+            arms.push(MatchArm { pattern, guard, body });
+        }
+        if !self.expect_peek(TokenKind::RightBrace) {
+            return None;
+        }
+
+        Some(Expr::new(
+            ExprKind::Match { value: Box::new(value), arms },
+            start.to(&self.current_token.span),
+        ))
+    }
+
+    /// ## parse_pattern
+    /// Parses a pattern starting at the current token.
+    fn parse_pattern(&mut self) -> Option<Pattern> {
+        let pattern = match self.current_token.kind.clone() {
+            TokenKind::Ident(name) if name == "_" => Pattern::Wildcard,
+            TokenKind::Ident(name) => Pattern::Binding(Ident(name)),
+            TokenKind::String(s) => Pattern::Literal(Literal::String(s)),
+            TokenKind::Boolean(b) => Pattern::Literal(Literal::Boolean(b)),
+            TokenKind::Null => Pattern::Literal(Literal::Null),
+            TokenKind::Number(_) | TokenKind::Minus => {
+                let start = self.parse_pattern_number()?;
+                match self.peek_token.kind {
+                    TokenKind::DotDot | TokenKind::DotDotEqual => {
+                        self.next_token();
+                        let inclusive = self.current_token(TokenKind::DotDotEqual);
+                        self.next_token();
+                        let end = self.parse_pattern_number()?;
+                        Pattern::Range(start, end, inclusive)
+                    }
+                    _ => Pattern::Literal(Literal::Number(start)),
+                }
+            }
+            TokenKind::LeftBracket => self.parse_array_pattern()?,
+            TokenKind::LeftBrace => self.parse_object_pattern()?,
+            TokenKind::Typeof => match self.peek_token.kind.clone() {
+                TokenKind::String(name) => {
+                    self.next_token();
+                    Pattern::Typeof(name)
+                }
+                _ => {
+                    self.peek_error(TokenKind::String(String::new()));
+                    return None;
+                }
+            },
             _ => {
-                None
+                self.pattern_error();
+                return None;
             }
         };
+        Some(pattern)
+    }
 
-        while !self.peek_token(&Token::Semicolon) && precedence < self.next_token_precedence() {
-            match self.peek_token {
-                Token::Plus
-                | Token::Minus
-                | Token::Asterisk
-                | Token::Equals
-                | Token::Slash
-                | Token::Percent
-                | Token::NotEquals
-                | Token::Less
-                | Token::LessEqual
-                | Token::Greater
-                | Token::GreaterEqual
-                | Token::AND
-                | Token::OR
-                | Token::XOR
-                | Token::In => {
+    fn parse_pattern_number(&mut self) -> Option<f64> {
+        let negative = self.current_token(TokenKind::Minus);
+        if negative {
+            self.next_token();
+        }
+        match self.current_token.kind {
+            TokenKind::Number(n) if negative => Some(-n),
+            TokenKind::Number(n) => Some(n),
+            _ => {
+                self.pattern_error();
+                None
+            }
+        }
+    }
+
+    fn parse_array_pattern(&mut self) -> Option<Pattern> {
+        let mut items = vec![];
+        let mut rest = None;
+        while !self.peek_token(&TokenKind::RightBracket) {
+            self.next_token();
+            if self.current_token(TokenKind::Ellipsis) {
+                rest = Some(Box::new(match &self.peek_token.kind {
+                    TokenKind::Ident(name) if name != "_" => {
+                        let name = Ident(name.clone());
+                        self.next_token();
+                        Pattern::Binding(name)
+                    }
+                    TokenKind::Ident(_) => {
+                        self.next_token();
+                        Pattern::Wildcard
+                    }
+                    _ => Pattern::Wildcard,
+                }));
+                break;
+            }
+            let item = self.parse_pattern()?;
+            items.push(self.parse_pattern_default(item)?);
+            if !self.peek_token(&TokenKind::RightBracket) && !self.expect_peek(TokenKind::Comma) {
+                return None;
+            }
+        }
+        if !self.expect_peek(TokenKind::RightBracket) {
+            return None;
+        }
+        Some(Pattern::Array { items, rest })
+    }
+
+    /// Parses the `= expr` default that may follow an element of an array or object pattern.
+    fn parse_pattern_default(&mut self, pattern: Pattern) -> Option<Pattern> {
+        if !self.peek_token(&TokenKind::Assign) {
+            return Some(pattern);
+        }
+        self.next_token();
+        self.next_token();
+        let default = self.parse_expr(Precedence::Lowest)?;
+        Some(Pattern::Default(Box::new(pattern), Box::new(default)))
+    }
+
+    fn parse_object_pattern(&mut self) -> Option<Pattern> {
+        let mut entries = vec![];
+        while !self.peek_token(&TokenKind::RightBrace) {
+            self.next_token();
+            match self.current_token.kind.clone() {
+                TokenKind::String(key) => {
+                    if !self.expect_peek(TokenKind::Colon) {
+                        return None;
+                    }
                     self.next_token();
-                    left = self.parse_infix_expr(left.unwrap());
+                    let value = self.parse_pattern()?;
+                    entries.push((key, self.parse_pattern_default(value)?));
+                }
+                TokenKind::Ident(name) => {
+                    let value = Pattern::Binding(Ident(name.clone()));
+                    entries.push((name, self.parse_pattern_default(value)?));
                 }
+                _ => {
+                    self.pattern_error();
+                    return None;
+                }
+            }
+            if !self.peek_token(&TokenKind::RightBrace) && !self.expect_peek(TokenKind::Comma) {
+                return None;
+            }
+        }
+        if !self.expect_peek(TokenKind::RightBrace) {
+            return None;
+        }
+        Some(Pattern::Object(entries))
+    }
 
-                Token::LeftShift => {
-                    self.lexer.next_token();
-                    self.next_token();
-                    left = self.parse_infix_expr(left.unwrap());
+    /// ## parse_for_expr
+    /// Parses `for (item in iterable) { }` and `for (key, item in iterable) { }`.
+    fn parse_for_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
+        if !self.expect_peek(TokenKind::LeftParen) {
+            return None;
+        }
+        let mut item = self.parse_loop_variable()?;
+        let mut key = None;
+        if self.peek_token(&TokenKind::Comma) {
+            self.next_token();
+            key = Some(item);
+            item = self.parse_loop_variable()?;
+        }
+        if !self.expect_peek(TokenKind::In) {
+            return None;
+        }
+        self.next_token();
+        let iterable = self.parse_expr(Precedence::Lowest)?;
+        if !self.expect_peek(TokenKind::RightParen) || !self.expect_peek(TokenKind::LeftBrace) {
+            return None;
+        }
+        let body = self.parse_block_statement();
+        Some(Expr::new(
+            ExprKind::For { key, item, iterable: Box::new(iterable), body },
+            start.to(&self.current_token.span),
+        ))
+    }
+
+    /// ## parse_try_expr
+    /// Parses `try { } catch (e) { } finally { }`.
+    /// The binding after `catch` may be left out, as in `catch { }`,
+    /// but a `try` needs a `catch` or a `finally`.
+    fn parse_try_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
+        if !self.expect_peek(TokenKind::LeftBrace) {
+            return None;
+        }
+        let body = self.parse_block_statement();
+
+        let mut catch = None;
+        if self.peek_token(&TokenKind::Catch) {
+            self.next_token();
+            let mut binding = None;
+            if self.peek_token(&TokenKind::LeftParen) {
+                self.next_token();
+                binding = Some(self.parse_loop_variable()?);
+                if !self.expect_peek(TokenKind::RightParen) {
+                    return None;
                 }
+            }
+            if !self.expect_peek(TokenKind::LeftBrace) {
+                return None;
+            }
+            catch = Some((binding, self.parse_block_statement()));
+        }
+
+        let mut finally = None;
+        if catch.is_none() || self.peek_token(&TokenKind::Finally) {
+            if !self.expect_peek(TokenKind::Finally) || !self.expect_peek(TokenKind::LeftBrace) {
+                return None;
+            }
+            finally = Some(self.parse_block_statement());
+        }
+
+        Some(Expr::new(
+            ExprKind::Try { body, catch, finally },
+            start.to(&self.current_token.span),
+        ))
+    }
+
+    fn parse_loop_variable(&mut self) -> Option<Ident> {
+        match &self.peek_token.kind {
+            TokenKind::Ident(name) => {
+                let name = Ident(name.clone());
+                self.next_token();
+                Some(name)
+            }
+            _ => {
+                self.peek_error(TokenKind::Ident(String::new()));
+                None
+            }
+        }
+    }
 
-                Token::RightShift => {
-                    self.lexer.next_token();
+    fn parse_expr(&mut self, precedence: Precedence) -> Option<Expr> {
+        let mut left: Expr = match self.current_token.kind {
+            TokenKind::Ident(_) => self.parse_ident(),
+            TokenKind::Bang | TokenKind::Minus | TokenKind::Plus => self.parse_prefix_expr(),
+            TokenKind::LeftParen => self.parse_grouped_expr(),
+            TokenKind::If => self.parse_if_expr(),
+            TokenKind::Func => self.parse_fn_expr(),
+            TokenKind::Number(_) => self.parse_int_literal(),
+            TokenKind::Boolean(_) => self.parse_boolean_literal(),
+            TokenKind::Null => Some(Expr::new(ExprKind::Literal(Literal::Null), self.current_token.span.clone())),
+            TokenKind::String(_) => self.parse_string_literal(),
+            TokenKind::TemplateStart(_) => self.parse_template_expr(),
+            TokenKind::LeftBracket => self.parse_array_literal(),
+            TokenKind::LeftBrace => self.parse_object_literal(),
+            TokenKind::Typeof => self.parse_typof_expr(),
+            TokenKind::Loop => self.parse_loop_expr(),
+            TokenKind::While => self.parse_while_expr(),
+            TokenKind::Match => self.parse_match_expr(),
+            TokenKind::For => self.parse_for_expr(),
+            TokenKind::Try => self.parse_try_expr(),
+            _ => {
+                self.expr_error();
+                None
+            }
+        }?;
+
+        while !self.peek_token(&TokenKind::Semicolon) && precedence < self.next_token_precedence() {
+            match self.peek_token.kind {
+                TokenKind::Plus
+                | TokenKind::Minus
+                | TokenKind::Asterisk
+                | TokenKind::Equals
+                | TokenKind::Slash
+                | TokenKind::Percent
+                | TokenKind::NotEquals
+                | TokenKind::Less
+                | TokenKind::LessEqual
+                | TokenKind::Greater
+                | TokenKind::GreaterEqual
+                | TokenKind::AND
+                | TokenKind::OR
+                | TokenKind::XOR
+                | TokenKind::LogicalAnd
+                | TokenKind::LogicalOr
+                | TokenKind::NullCoalesce
+                | TokenKind::LeftShift
+                | TokenKind::RightShift
+                | TokenKind::In => {
                     self.next_token();
-                    left = self.parse_infix_expr(left.unwrap());
+                    left = self.parse_infix_expr(left)?;
                 }
 
-                Token::LeftParen => {
+                TokenKind::LeftParen => {
                     self.next_token();
-                    left = self.parse_call_expr(left.unwrap());
+                    left = self.parse_call_expr(left, false)?;
                 }
-                Token::LeftBracket => {
+                TokenKind::LeftBracket => {
                     self.next_token();
-                    left = self.parse_index_expr(left.unwrap());
+                    left = self.parse_index_expr(left, false)?;
                 }
-                _ => return left,
+                TokenKind::Dot => {
+                    self.next_token();
+                    left = self.parse_member_expr(left, false)?;
+                }
+                TokenKind::QuestionDot => {
+                    self.next_token();
+                    left = self.parse_optional_expr(left)?;
+                }
+                TokenKind::Question => {
+                    self.next_token();
+                    left = self.parse_ternary_expr(left)?;
+                }
+                TokenKind::DotDot | TokenKind::DotDotEqual => {
+                    self.next_token();
+                    left = self.parse_range_expr(left)?;
+                }
+                _ => return Some(left),
             }
         }
 
-        left
+        Some(left)
     }
 
     fn parse_object_literal(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
         let mut obj = vec![];
-        while !self.peek_token(&Token::RightBrace) {
+        while !self.peek_token(&TokenKind::RightBrace) {
             self.next_token();
-            let key = match self.parse_expr(Precedence::Lowest) {
-                Some(o) => o,
-                None => return None,
-            };
-            if !self.expect_peek(Token::Colon) {
+            let key = self.parse_expr(Precedence::Lowest)?;
+            if !self.expect_peek(TokenKind::Colon) {
                 return None;
             }
 
             self.next_token();
-            let val = match self.parse_expr(Precedence::Lowest) {
-                Some(o) => o,
-                None => return None,
-            };
+            let val = self.parse_expr(Precedence::Lowest)?;
             obj.push((key, val));
-            if !self.peek_token(&Token::RightBrace) && !self.expect_peek(Token::Comma) {
+            if !self.peek_token(&TokenKind::RightBrace) && !self.expect_peek(TokenKind::Comma) {
                 return None;
             }
         }
-        if !self.expect_peek(Token::RightBrace) {
+        if !self.expect_peek(TokenKind::RightBrace) {
             return None;
         }
 
-        Some(Expr::Literal(Literal::Object(obj)))
+        Some(Expr::new(ExprKind::Literal(Literal::Object(obj)), start.to(&self.current_token.span)))
     }
 
     fn parse_int_literal(&mut self) -> Option<Expr> {
-        match self.current_token {
-            Token::Number(ref mut int) => Some(Expr::Literal(Literal::Number(*int))),
+        match self.current_token.kind {
+            TokenKind::Number(int) => Some(Expr::new(
+                ExprKind::Literal(Literal::Number(int)),
+                self.current_token.span.clone(),
+            )),
             _ => None,
         }
     }
 
     fn parse_boolean_literal(&mut self) -> Option<Expr> {
-        match self.current_token {
-            Token::Boolean(boolean) => Some(Expr::Literal(Literal::Boolean(boolean))),
+        match self.current_token.kind {
+            TokenKind::Boolean(boolean) => Some(Expr::new(
+                ExprKind::Literal(Literal::Boolean(boolean)),
+                self.current_token.span.clone(),
+            )),
             _ => None,
         }
     }
 
     fn parse_string_literal(&mut self) -> Option<Expr> {
-        match self.current_token {
-            Token::String(ref mut str) => Some(Expr::Literal(Literal::String(str.clone()))),
+        match self.current_token.kind {
+            TokenKind::String(ref str) => Some(Expr::new(
+                ExprKind::Literal(Literal::String(str.clone())),
+                self.current_token.span.clone(),
+            )),
             _ => None,
         }
     }
 
+    /// ## parse_template_expr
+    /// Parses a template literal with embedded expressions,
+    /// starting at its `TemplateStart` token.
+    /// Each expression must be followed by the `}` that closes it,
+    /// which the lexer turns into the next `TemplateMiddle` or `TemplateEnd`.
+    fn parse_template_expr(&mut self) -> Option<Expr> {
+        let start = self.current_token.span.clone();
+        let mut parts = vec![];
+        loop {
+            match self.current_token.kind.clone() {
+                TokenKind::TemplateStart(text) | TokenKind::TemplateMiddle(text) => {
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::String(text));
+                    }
+                }
+                TokenKind::TemplateEnd(text) => {
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::String(text));
+                    }
+                    break;
+                }
+                _ => {
+                    let msg = format!("Expected a template literal, got {} instead", self.current_token);
+                    self.errors.push(
+                        Diagnostic::error(diagnostic::UNEXPECTED_TOKEN, msg, self.current_token.span.clone())
+                            .with_primary_label("expected a template literal"),
+                    );
+                    return None;
+                }
+            }
+            self.next_token();
+            parts.push(TemplatePart::Expr(self.parse_expr(Precedence::Lowest)?));
+            match self.peek_token.kind {
+                TokenKind::TemplateMiddle(_) | TokenKind::TemplateEnd(_) => self.next_token(),
+                _ => {
+                    self.peek_error(TokenKind::RightBrace);
+                    return None;
+                }
+            }
+        }
+        Some(Expr::new(ExprKind::Template(parts), start.to(&self.current_token.span)))
+    }
+
     fn parse_array_literal(&mut self) -> Option<Expr> {
-        self.parse_expr_list(Token::RightBracket)
-            .map(|list| Expr::Literal(Literal::Array(list)))
+        let start = self.current_token.span.clone();
+        self.parse_expr_list(TokenKind::RightBracket).map(|list| {
+            Expr::new(
+                ExprKind::Literal(Literal::Array(list)),
+                start.to(&self.current_token.span),
+            )
+        })
     }
 
     
 
-    fn parse_expr_list(&mut self, end: Token) -> Option<Vec<Expr>> {
+    fn parse_expr_list(&mut self, end: TokenKind) -> Option<Vec<Expr>> {
         let mut list = vec![];
         if self.peek_token(&end) {
             self.next_token();
@@ -339,9 +920,9 @@ impl Parser {
             Some(a) => list.push(a),
             None => return None,
         };
-        while self.peek_token(&Token::Comma) {
+        while self.peek_token(&TokenKind::Comma) {
             self.next_token();
-            if self.peek_token(&Token::RightBracket) {
+            if self.peek_token(&TokenKind::RightBracket) {
                 break;
             }
             self.next_token();
@@ -360,238 +941,400 @@ impl Parser {
     }
 
     fn parse_ident(&mut self) -> Option<Expr> {
-        match self.current_token {
-            Token::Ident(ref mut ident) => Some(Expr::Ident(Ident(ident.clone()))),
+        match self.current_token.kind {
+            TokenKind::Ident(ref ident) => Some(Expr::new(
+                ExprKind::Ident(Ident(ident.clone())),
+                self.current_token.span.clone(),
+            )),
             _ => None,
         }
     }
 
     fn parse_prefix_expr(&mut self) -> Option<Expr> {
-        let prefix = match self.current_token {
-            Token::Bang => Prefix::Exclamation,
-            Token::Minus => Prefix::Minus,
-            Token::Plus => Prefix::Plus,
+        let start = self.current_token.span.clone();
+        let prefix = match self.current_token.kind {
+            TokenKind::Bang => Prefix::Exclamation,
+            TokenKind::Minus => Prefix::Minus,
+            TokenKind::Plus => Prefix::Plus,
             _ => return None,
         };
 
         self.next_token();
 
-        self.parse_expr(Precedence::Prefix)
-            .map(|expr| Expr::Prefix(prefix, Box::new(expr)))
+        self.parse_expr(Precedence::Prefix).map(|expr| {
+            let span = start.to(&expr.span);
+            Expr::new(ExprKind::Prefix(prefix, Box::new(expr)), span)
+        })
     }
 
     fn parse_infix_expr(&mut self, left: Expr) -> Option<Expr> {
-        let infix = match self.current_token {
-            Token::Plus => Infix::Plus,
-            Token::Minus => Infix::Minus,
-            Token::Slash => Infix::Divide,
-            Token::Asterisk => Infix::Times,
-            Token::Percent => Infix::Modulo,
-            Token::Equals => Infix::Equals,
-            Token::NotEquals => Infix::NotEquals,
-            Token::Less => Infix::LessThan,
-            Token::Greater => Infix::GreaterThan,
-            Token::LessEqual => Infix::LessThanEqual,
-            Token::GreaterEqual => Infix::GreaterThanEqual,
-            Token::LeftShift => Infix::LeftShift,
-            Token::RightShift => Infix::RightShift,
-            Token::AND => Infix::AND,
-            Token::OR => Infix::OR,
-            Token::XOR => Infix::XOR,
-            Token::In => Infix::In,
+        let infix = match self.current_token.kind {
+            TokenKind::Plus => Infix::Plus,
+            TokenKind::Minus => Infix::Minus,
+            TokenKind::Slash => Infix::Divide,
+            TokenKind::Asterisk => Infix::Times,
+            TokenKind::Percent => Infix::Modulo,
+            TokenKind::Equals => Infix::Equals,
+            TokenKind::NotEquals => Infix::NotEquals,
+            TokenKind::Less => Infix::LessThan,
+            TokenKind::Greater => Infix::GreaterThan,
+            TokenKind::LessEqual => Infix::LessThanEqual,
+            TokenKind::GreaterEqual => Infix::GreaterThanEqual,
+            TokenKind::LeftShift => Infix::LeftShift,
+            TokenKind::RightShift => Infix::RightShift,
+            TokenKind::AND => Infix::AND,
+            TokenKind::OR => Infix::OR,
+            TokenKind::XOR => Infix::XOR,
+            TokenKind::LogicalAnd => Infix::LogicalAnd,
+            TokenKind::LogicalOr => Infix::LogicalOr,
+            TokenKind::NullCoalesce => Infix::NullCoalesce,
+            TokenKind::In => Infix::In,
             _ => return None,
         };
 
         let precedence = self.current_token_precedence();
         self.next_token();
 
-        self.parse_expr(precedence)
-            .map(|e| Expr::Infix(infix, Box::new(left), Box::new(e)))
+        self.parse_expr(precedence).map(|e| {
+            let span = left.span.to(&e.span);
+            Expr::new(ExprKind::Infix(infix, Box::new(left), Box::new(e)), span)
+        })
     }
 
     fn parse_grouped_expr(&mut self) -> Option<Expr> {
-        let exp = self.parse_expr(Precedence::Lowest);
-        if !self.expect_peek(Token::RightParen) {
+        self.next_token();
+        let exp = self.parse_expr(Precedence::Lowest)?;
+        if !self.expect_peek(TokenKind::RightParen) {
             return None;
         }
-        exp
+        Some(exp)
     }
 
     fn parse_if_expr(&mut self) -> Option<Expr> {
-        if !self.expect_peek(Token::LeftParen) {
+        let start = self.current_token.span.clone();
+        if !self.expect_peek(TokenKind::LeftParen) {
             return None;
         }
 
         self.next_token();
 
-        let expr: Expr = match self.parse_expr(Precedence::Lowest) {
-            Some(e) => e,
-            None => return None,
-        };
+        let expr: Expr = self.parse_expr(Precedence::Lowest)?;
 
-        if !self.expect_peek(Token::RightParen) || !self.expect_peek(Token::LeftBrace) {
+        if !self.expect_peek(TokenKind::RightParen) || !self.expect_peek(TokenKind::LeftBrace) {
             return None;
         }
 
         let cons: Vec<Statement> = self.parse_block_statement();
         let mut alternative: Option<Vec<Statement>> = None;
-        if self.peek_token(&Token::Else) {
+        if self.peek_token(&TokenKind::Else) {
             self.next_token();
 
-            if self.peek_token(&Token::If) {
+            if self.peek_token(&TokenKind::If) {
                 self.next_token();
-                let else_if = self.parse_if_expr();
-                alternative = Some(vec![Statement::Expression(else_if.unwrap())]);
-            } else if !self.expect_peek(Token::LeftBrace) {
+                let else_if = self.parse_if_expr()?;
+                let span = else_if.span.clone();
+                alternative = Some(vec![Statement::new(StatementKind::Expression(else_if), span)]);
+            } else if !self.expect_peek(TokenKind::LeftBrace) {
                 return None;
             } else {
                 alternative = Some(self.parse_block_statement())
             };
         }
 
-        Some(Expr::If {
-            cond: Box::new(expr),
-            then: Box::new(cons),
-            else_: alternative,
-        })
+        Some(Expr::new(
+            ExprKind::If {
+                cond: Box::new(expr),
+                then: Box::new(cons),
+                else_: alternative,
+            },
+            start.to(&self.current_token.span),
+        ))
     }
 
     fn parse_fn_expr(&mut self) -> Option<Expr> {
-        if !self.expect_peek(Token::LeftParen) {
+        let start = self.current_token.span.clone();
+        if !self.expect_peek(TokenKind::LeftParen) {
             return None;
         }
-        let params = match self.parse_params() {
-            Some(s) => s,
-            None => return None,
-        };
+        let params = self.parse_params()?;
         self.next_token();
         let body = self.parse_block_statement();
         
-        Some(Expr::Fun { params, body })
+        Some(Expr::new(ExprKind::Fun { params, body }, start.to(&self.current_token.span)))
     }
 
-    fn token_to_precedence(tok: &Token) -> Precedence {
+    fn token_to_precedence(tok: &TokenKind) -> Precedence {
         match tok {
-            Token::Equals | Token::NotEquals => Precedence::Equals,
-            Token::Less | Token::LessEqual => Precedence::LessGreater,
-            Token::Greater | Token::GreaterEqual => Precedence::LessGreater,
-            Token::Plus | Token::Minus => Precedence::Sum,
-            Token::Slash | Token::Asterisk | Token::Percent => Precedence::Product,
-            Token::LeftBracket => Precedence::Index,
-            Token::LeftParen => Precedence::Call,
-            Token::In => Precedence::In,
-            Token::LeftShift => Precedence::LeftShift,
-            Token::RightShift => Precedence::RightShift,
-            Token::AND => Precedence::AND,
-            Token::OR => Precedence::OR,
-            Token::XOR => Precedence::XOR,
+            TokenKind::Equals | TokenKind::NotEquals => Precedence::Equals,
+            TokenKind::Less | TokenKind::LessEqual => Precedence::Compare,
+            TokenKind::Greater | TokenKind::GreaterEqual => Precedence::Compare,
+            TokenKind::In => Precedence::Compare,
+            TokenKind::DotDot | TokenKind::DotDotEqual => Precedence::Range,
+            TokenKind::LeftShift | TokenKind::RightShift => Precedence::Shift,
+            TokenKind::Plus | TokenKind::Minus => Precedence::Sum,
+            TokenKind::Slash | TokenKind::Asterisk | TokenKind::Percent => Precedence::Product,
+            TokenKind::LeftBracket | TokenKind::Dot | TokenKind::QuestionDot => Precedence::Index,
+            TokenKind::Question => Precedence::Ternary,
+            TokenKind::LeftParen => Precedence::Call,
+            TokenKind::AND => Precedence::BitAnd,
+            TokenKind::OR => Precedence::BitOr,
+            TokenKind::XOR => Precedence::BitXor,
+            TokenKind::LogicalAnd => Precedence::LogicalAnd,
+            TokenKind::LogicalOr => Precedence::LogicalOr,
+            TokenKind::NullCoalesce => Precedence::NullCoalesce,
             _ => Precedence::Lowest,
         }
     }
 
-    fn parse_params(&mut self) -> Option<Vec<Ident>> {
-        let mut idents: Vec<Ident> = vec![];
-        if self.peek_token(&Token::RightParen) {
+    /// ## parse_params
+    /// Parses a parameter list such as `(a, [b, c], d = 1, ...rest)`.
+    /// A rest parameter has to come last.
+    fn parse_params(&mut self) -> Option<Params> {
+        let mut params = Params::default();
+        if self.peek_token(&TokenKind::RightParen) {
             self.next_token();
-            return Some(idents);
+            return Some(params);
         }
 
-        self.next_token();
-        match self.current_token {
-            Token::Ident(ref mut ident) => idents.push(Ident(ident.clone())),
-            _ => {
-                self.errors.push(
-                   format!("Expected identifier as parameter name. Got: {}", self.current_token)
-                );
-                return None;
-            }
-        };
-
-        while self.peek_token(&Token::Comma) {
+        loop {
             self.next_token();
+            if self.current_token(TokenKind::Ellipsis) {
+                self.next_token();
+                match self.current_token.kind.clone() {
+                    TokenKind::Ident(name) => params.rest = Some(Ident(name)),
+                    _ => {
+                        self.param_error();
+                        return None;
+                    }
+                }
+                break;
+            }
+            let param = self.parse_param()?;
+            params.positional.push(self.parse_pattern_default(param)?);
+            if !self.peek_token(&TokenKind::Comma) {
+                break;
+            }
             self.next_token();
-            match self.current_token {
-                Token::Ident(ref mut ident) => idents.push(Ident(ident.clone())),
-                _ => return None,
-            };
         }
 
-        if !self.expect_peek(Token::RightParen) {
+        if !self.expect_peek(TokenKind::RightParen) {
             return None;
         }
 
-        Some(idents)
+        Some(params)
     }
 
-    fn parse_call_arguments(&mut self) -> Option<Vec<Expr>> {
-        let mut args: Vec<Expr> = vec![];
+    /// A parameter is a name, or an array or object pattern to destructure the argument with.
+    fn parse_param(&mut self) -> Option<Pattern> {
+        match self.current_token.kind {
+            TokenKind::Ident(ref ident) => Some(Pattern::Binding(Ident(ident.clone()))),
+            TokenKind::LeftBracket | TokenKind::LeftBrace => self.parse_pattern(),
+            _ => {
+                self.param_error();
+                None
+            }
+        }
+    }
 
-        if self.peek_token(&Token::RightParen) {
+    fn parse_call_arguments(&mut self) -> Option<Vec<Argument>> {
+        let mut args: Vec<Argument> = vec![];
+
+        if self.peek_token(&TokenKind::RightParen) {
             self.next_token();
             return Some(args);
         }
 
         self.next_token();
-        match self.parse_expr(Precedence::Lowest) {
-            Some(e) => args.push(e),
-            None => return None,
-        };
+        args.push(self.parse_call_argument()?);
 
-        while self.peek_token(&Token::Comma) {
+        while self.peek_token(&TokenKind::Comma) {
             self.next_token();
             self.next_token();
-
-            match self.parse_expr(Precedence::Lowest) {
-                Some(e) => args.push(e),
-                None => return None,
-            };
+            args.push(self.parse_call_argument()?);
         }
 
-        if !self.expect_peek(Token::RightParen) {
+        if !self.expect_peek(TokenKind::RightParen) {
             return None;
         }
 
         Some(args)
     }
 
-    fn parse_index_expr(&mut self, left: Expr) -> Option<Expr> {
+    /// An argument is an expression, `...array` to spread an array,
+    /// or `name: expr` to pass the parameter called `name`.
+    fn parse_call_argument(&mut self) -> Option<Argument> {
+        if self.current_token(TokenKind::Ellipsis) {
+            self.next_token();
+            return Some(Argument::Spread(self.parse_expr(Precedence::Lowest)?));
+        }
+        if let TokenKind::Ident(name) = self.current_token.kind.clone() {
+            if self.peek_token(&TokenKind::Colon) {
+                self.next_token();
+                self.next_token();
+                let value = self.parse_expr(Precedence::Lowest)?;
+                return Some(Argument::Named(Ident(name), value));
+            }
+        }
+        Some(Argument::Positional(self.parse_expr(Precedence::Lowest)?))
+    }
+
+    /// ## parse_index_expr
+    /// Parses `array[index]` and the slices `array[start:end]`, `array[start:]` and `array[:end]`.
+    /// `optional` is set for `array?.[index]`.
+    fn parse_index_expr(&mut self, left: Expr, optional: bool) -> Option<Expr> {
         self.next_token();
-        let expr = match self.parse_expr(Precedence::Lowest) {
-            Some(e) => e,
-            None => return None,
+        let expr = if self.current_token(TokenKind::Colon) {
+            None
+        } else {
+            Some(self.parse_expr(Precedence::Lowest)?)
         };
-        if !self.expect_peek(Token::RightBracket) {
+        if expr.is_some() && self.peek_token(&TokenKind::Colon) {
+            self.next_token();
+        }
+        if self.current_token(TokenKind::Colon) {
+            let end = if self.peek_token(&TokenKind::RightBracket) {
+                None
+            } else {
+                self.next_token();
+                Some(Box::new(self.parse_expr(Precedence::Lowest)?))
+            };
+            if !self.expect_peek(TokenKind::RightBracket) {
+                return None;
+            }
+            let span = left.span.to(&self.current_token.span);
+            return Some(Expr::new(
+                ExprKind::Slice {
+                    array: Box::new(left),
+                    start: expr.map(Box::new),
+                    end,
+                    optional,
+                },
+                span,
+            ));
+        }
+        let expr = expr?;
+        if !self.expect_peek(TokenKind::RightBracket) {
             return None;
         }
 
-        Some(Expr::Index {
-            array: Box::new(left),
-            index: Box::new(expr),
-        })
+        let span = left.span.to(&self.current_token.span);
+        Some(Expr::new(
+            ExprKind::Index {
+                array: Box::new(left),
+                index: Box::new(expr),
+                optional,
+            },
+            span,
+        ))
     }
 
-    fn parse_call_expr(&mut self, left: Expr) -> Option<Expr> {
-        let args = match self.parse_call_arguments() {
-            Some(e) => e,
-            None => return None,
+    /// ## parse_ternary_expr
+    /// Parses `cond ? then : else_`, with the current token on the `?`.
+    /// The else branch is parsed at the lowest precedence,
+    /// so `a ? b : c ? d : e` groups as `a ? b : (c ? d : e)`.
+    fn parse_ternary_expr(&mut self, cond: Expr) -> Option<Expr> {
+        self.next_token();
+        let then = self.parse_expr(Precedence::Lowest)?;
+        if !self.expect_peek(TokenKind::Colon) {
+            return None;
+        }
+        self.next_token();
+        let else_ = self.parse_expr(Precedence::Lowest)?;
+        let span = cond.span.to(&else_.span);
+        Some(Expr::new(
+            ExprKind::Ternary {
+                cond: Box::new(cond),
+                then: Box::new(then),
+                else_: Box::new(else_),
+            },
+            span,
+        ))
+    }
+
+    /// ## parse_optional_expr
+    /// Parses what follows `?.`: a property in `a?.b`, an index in `a?.[i]`
+    /// or the arguments in `f?.(x)`.
+    fn parse_optional_expr(&mut self, left: Expr) -> Option<Expr> {
+        match self.peek_token.kind {
+            TokenKind::LeftBracket => {
+                self.next_token();
+                self.parse_index_expr(left, true)
+            }
+            TokenKind::LeftParen => {
+                self.next_token();
+                self.parse_call_expr(left, true)
+            }
+            _ => self.parse_member_expr(left, true),
+        }
+    }
+
+    fn parse_member_expr(&mut self, left: Expr, optional: bool) -> Option<Expr> {
+        let property = match &self.peek_token.kind {
+            TokenKind::Ident(name) => Ident(name.clone()),
+            _ => {
+                self.peek_error(TokenKind::Ident(String::new()));
+                return None;
+            }
         };
+        self.next_token();
+        let span = left.span.to(&self.current_token.span);
+        Some(Expr::new(
+            ExprKind::Member {
+                object: Box::new(left),
+                property,
+                optional,
+            },
+            span,
+        ))
+    }
 
-        Some(Expr::Call {
-            function: Box::new(left),
-            args,
-        })
+    fn parse_range_expr(&mut self, left: Expr) -> Option<Expr> {
+        let inclusive = self.current_token(TokenKind::DotDotEqual);
+        let precedence = self.current_token_precedence();
+        self.next_token();
+        let end = self.parse_expr(precedence)?;
+        let span = left.span.to(&end.span);
+        Some(Expr::new(
+            ExprKind::Range {
+                start: Box::new(left),
+                end: Box::new(end),
+                inclusive,
+            },
+            span,
+        ))
+    }
+
+    fn parse_call_expr(&mut self, left: Expr, optional: bool) -> Option<Expr> {
+        let args = self.parse_call_arguments()?;
+
+        let span = left.span.to(&self.current_token.span);
+        Some(Expr::new(
+            ExprKind::Call {
+                function: Box::new(left),
+                args,
+                optional,
+            },
+            span,
+        ))
     }
 
-    fn peek_token(&self, t: &Token) -> bool {
-        self.peek_token == *t
+    /// Consumes the `;` ending a statement, if there is one.
+    fn skip_semicolon(&mut self) {
+        if self.peek_token(&TokenKind::Semicolon) {
+            self.next_token();
+        }
     }
 
-    fn current_token(&self, t: Token) -> bool {
-        self.current_token == t
+    fn peek_token(&self, t: &TokenKind) -> bool {
+        self.peek_token.kind == *t
     }
 
-    fn expect_peek(&mut self, t: Token) -> bool {
-        if let Token::Ident(..) = t {
+    fn current_token(&self, t: TokenKind) -> bool {
+        self.current_token.kind == t
+    }
+
+    fn expect_peek(&mut self, t: TokenKind) -> bool {
+        if let TokenKind::Ident(..) = t {
             self.next_token();
             return true;
         }
@@ -605,20 +1348,65 @@ impl Parser {
         }
     }
 
-    fn peek_error(&mut self, t: Token) {
+    fn peek_error(&mut self, t: TokenKind) {
+        if self.peek_token.kind == TokenKind::Eof && self.input_unterminated() {
+            return;
+        }
         let msg = format!(
             "Expected next token to be {}, got {} instead",
             t,
             self.peek_token
         );
-        self.errors.push(msg);
+        let label = format!("expected {}", t);
+        self.errors.push(
+            Diagnostic::error(diagnostic::UNEXPECTED_TOKEN, msg, self.peek_token.span.clone())
+                .with_primary_label(&label),
+        );
+    }
+
+    fn expr_error(&mut self) {
+        if self.current_token.kind == TokenKind::Eof && self.input_unterminated() {
+            return;
+        }
+        let msg = format!("Expected an expression, got {} instead", self.current_token);
+        self.errors.push(
+            Diagnostic::error(diagnostic::EXPECTED_EXPRESSION, msg, self.current_token.span.clone())
+                .with_primary_label("expected an expression"),
+        );
+    }
+
+    /// Whether the lexer reported a string or comment that runs to the end of the input.
+    /// The tokens missing after it are a consequence of that error, so they are not reported again.
+    fn input_unterminated(&self) -> bool {
+        self.errors
+            .iter()
+            .any(|e| e.code == diagnostic::UNTERMINATED_STRING || e.code == diagnostic::UNTERMINATED_COMMENT)
+    }
+
+    fn pattern_error(&mut self) {
+        let msg = format!("Expected a pattern, got {} instead", self.current_token);
+        self.errors.push(
+            Diagnostic::error(diagnostic::INVALID_PATTERN, msg, self.current_token.span.clone())
+                .with_primary_label("expected a pattern"),
+        );
+    }
+
+    fn param_error(&mut self) {
+        let msg = format!(
+            "Expected identifier as parameter name. Got: {}",
+            self.current_token
+        );
+        self.errors.push(
+            Diagnostic::error(diagnostic::INVALID_PARAMETER, msg, self.current_token.span.clone())
+                .with_primary_label("expected identifier"),
+        );
     }
 
     fn current_token_precedence(&mut self) -> Precedence {
-        Self::token_to_precedence(&self.current_token)
+        Self::token_to_precedence(&self.current_token.kind)
     }
 
     fn next_token_precedence(&mut self) -> Precedence {
-        Self::token_to_precedence(&self.peek_token)
+        Self::token_to_precedence(&self.peek_token.kind)
     }
-}
\ No newline at end of file
+}
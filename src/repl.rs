@@ -1,4 +1,5 @@
 use crate::{
+    diagnostic::{render_diagnostics, Diagnostic},
     evaluation::{store::Store, object::Object, Eval},
     lexer::Lexer,
     parser::Parser,
@@ -11,9 +12,7 @@ use std::{
 
 pub fn start() {
     let env = Store::new();
-    let mut evaluator = Eval {
-        store: Rc::new(RefCell::new(env)),
-    };
+    let mut evaluator = Eval::new(Rc::new(RefCell::new(env)));
     loop {
         print!(">> ");
         let _ = stdout().flush();
@@ -25,11 +24,11 @@ pub fn start() {
             continue;
         }
 
-        let lexer = Lexer::new(input_string);
+        let lexer = Lexer::new(input_string.clone());
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
         if !parser.errors.is_empty() {
-            print_parse_errors(parser.errors);
+            print_parse_errors(&parser.errors, &input_string);
             continue;
         }
         let res = evaluator.eval(program);
@@ -37,8 +36,6 @@ pub fn start() {
     }
 }
 
-fn print_parse_errors(errors: Vec<String>) {
-    for e in errors.iter() {
-        println!("\t{}", e);
-    }
+fn print_parse_errors(errors: &[Diagnostic], source: &str) {
+    println!("{}", render_diagnostics(errors, source));
 }
\ No newline at end of file
@@ -1,17 +1,17 @@
 use std::collections::HashMap;
 
-use crate::evaluation::object::Object;
+use crate::evaluation::object::{Arity, Object};
 
 use super::Res;
 
 /// Adds the standard library to the global environment.
 pub fn add_globals() -> Res {
     let mut globals = HashMap::new();
-    globals.insert(String::from("pop"), Object::Inbuilt(pop));
-    globals.insert(String::from("head"), Object::Inbuilt(head));
-    globals.insert(String::from("tail"), Object::Inbuilt(tail));
-    globals.insert(String::from("push"), Object::Inbuilt(push));
-    globals.insert(String::from("includes"), Object::Inbuilt(includes));
+    globals.insert(String::from("pop"), Object::Inbuilt(pop, Arity::exactly(1)));
+    globals.insert(String::from("head"), Object::Inbuilt(head, Arity::exactly(1)));
+    globals.insert(String::from("tail"), Object::Inbuilt(tail, Arity::exactly(1)));
+    globals.insert(String::from("push"), Object::Inbuilt(push, Arity::exactly(2)));
+    globals.insert(String::from("includes"), Object::Inbuilt(includes, Arity::exactly(2)));
     Res {
         globals,
         raw: Some("
@@ -39,82 +39,51 @@ pub fn add_globals() -> Res {
 /// # Arguments
 /// * `args` - The array to push onto.
 pub fn push(args: Vec<Object>) -> Object {
-    if args.len() != 2 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 2.",
-            args.len()
-        ));
-    }
-
     match &args[0] {
         Object::Array(a) => {
             let mut array = a.clone();
             array.push(args[1].clone());
             Object::Array(array)
         }
-        o => Object::Error(format!("First argument must be an array. Got {}", o)),
+        o => Object::error(format!("First argument must be an array. Got {}", o)),
     }
 }
 
 pub fn pop(args: Vec<Object>) -> Object {
-    if args.len() != 1 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 1.",
-            args.len()
-        ));
-    }
     match &args[0] {
         Object::Array(a) => {
             let mut array = a.clone();
             array.pop();
             Object::Array(array)
         }
-        o => Object::Error(format!("First argument must be an array. Got {}", o)),
+        o => Object::error(format!("First argument must be an array. Got {}", o)),
     }
 }
 
 pub fn head (args: Vec<Object>) -> Object {
-    if args.len() != 1 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 1.",
-            args.len()
-        ));
-    }
     match &args[0] {
         Object::Array(a) => {
             let mut array = a.clone();
             array.pop();
             Object::Array(array)
         }
-        o => Object::Error(format!("First argument must be an array. Got {}", o)),
+        o => Object::error(format!("First argument must be an array. Got {}", o)),
     }
 }
 
 pub fn tail (args: Vec<Object>) -> Object {
-    if args.len() != 1 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 1.",
-            args.len()
-        ));
-    }
     match &args[0] {
-        Object::Array(a) => Object::Array(a[1..].to_vec()),
-        o => Object::Error(format!("First argument must be an array. Got {}", o)),
+        Object::Array(a) => Object::Array(a.get(1..).unwrap_or(&[]).to_vec()),
+        o => Object::error(format!("First argument must be an array. Got {}", o)),
     }
 }
 
 pub fn includes (args: Vec<Object>) -> Object {
-    if args.len() != 2 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 2.",
-            args.len()
-        ));
-    }
     match &args[0] {
         Object::Array(a) => {
             let array = a.clone();
             Object::Bool(array.contains(&args[1]))
         }
-        o => Object::Error(format!("First argument must be an array. Got {}", o)),
+        o => Object::error(format!("First argument must be an array. Got {}", o)),
     }
 }
\ No newline at end of file
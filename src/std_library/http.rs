@@ -1,30 +1,25 @@
 use std::{format, collections::HashMap, str::FromStr};
 use reqwest::{header::{HeaderMap, self}};
 
-use crate::evaluation::object::Object;
+use crate::evaluation::object::{Arity, Object};
 
 use super::Res;
 
 pub fn add_globals() -> Res {
     let mut globals = HashMap::new();
-    globals.insert("request".to_string(), Object::Inbuilt(request));
+    globals.insert("request".to_string(), Object::Inbuilt(request, Arity::between(2, 4)));
     Res { globals, raw: None }
 }
 
+#[allow(clippy::mutable_key_type)]
 pub fn request(args: Vec<Object>) -> Object {
-    if args.len() < 2 || args.len() > 4 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 2-4.",
-            args.len()
-        ));
-    }
     let method = match &args[0] {
         Object::String(s) => s,
-        o => return Object::Error(format!("First argument must be a string. Got {}", o)),
+        o => return Object::error(format!("First argument must be a string. Got {}", o)),
     };
     let url = match &args[1] {
         Object::String(s) => s,
-        o => return Object::Error(format!("Second argument must be a string. Got {}", o)),
+        o => return Object::error(format!("Second argument must be a string. Got {}", o)),
     };
     let headers = match args.get(2) {
         Some(Object::Object(h)) =>{
@@ -36,21 +31,22 @@ pub fn request(args: Vec<Object>) -> Object {
                         headers.insert(key, v.clone().parse().unwrap());
                     },
                     _ => {
-                        return Object::Error(format!("Headers must be a map of strings. Got {}", args[2]));
+                        return Object::error(format!("Headers must be a map of strings. Got {}", args[2]));
                     }
                 }
             }
             headers
         },
-        Some(o) => return Object::Error(format!("Third argument must be an Object. Got {}", o)),
+        Some(o) => return Object::error(format!("Third argument must be an Object. Got {}", o)),
         None => HeaderMap::new(),
     };
 
     let client = reqwest::blocking::Client::new();
 
-    let body: String = match &args[3] {
-        Object::String(s) => s.clone(),
-        o=> o.to_string(),
+    let body: String = match args.get(3) {
+        Some(Object::String(s)) => s.clone(),
+        Some(o) => o.to_string(),
+        None => String::new(),
     };
 
     let response = match method.as_str() {
@@ -58,7 +54,7 @@ pub fn request(args: Vec<Object>) -> Object {
         "POST" => client.post(url).headers(headers).body::<String>(body).send(),
         "PUT" => client.put(url).headers(headers).body::<String>(body).send(),
         "DELETE" => client.delete(url).headers(headers).send(),
-        _ => return Object::Error(format!("Unsupported HTTP method {}", method)),
+        _ => return Object::error(format!("Unsupported HTTP method {}", method)),
     };
 
     match response {
@@ -78,7 +74,7 @@ pub fn request(args: Vec<Object>) -> Object {
             result.insert(Object::String("body".to_string()), Object::String(body.to_string()));
             Object::Object(result)
         },
-        Err(e) => Object::Error(format!("{}", e)),
+        Err(e) => Object::error(format!("{}", e)),
     }
 
 
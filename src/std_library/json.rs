@@ -2,20 +2,21 @@ use std::{collections::HashMap};
 
 use serde_json::Value;
 
-use crate::evaluation::object::Object;
+use crate::evaluation::object::{Arity, Object};
 
 use super::Res;
 
 /// Adds the standard library to the global environment.
 pub fn add_globals() -> Res {
     let mut globals = HashMap::new();
-    globals.insert(String::from("parse_json"), Object::Inbuilt(parse_json));
+    globals.insert(String::from("parse_json"), Object::Inbuilt(parse_json, Arity::exactly(1)));
     Res {
         globals,
         raw:None
     }
 }
 
+#[allow(clippy::mutable_key_type)]
 pub fn parse_json(args: Vec<Object>) -> Object {
     match &args[0] {
         Object::String(s) => {
@@ -44,10 +45,10 @@ pub fn parse_json(args: Vec<Object>) -> Object {
                                 };
                                 hash.insert(key, value);
                             }
-                            return Object::Object(hash);
+                            Object::Object(hash)
                         }
                         Value::Array(arr) => {
-                            return Object::Array(arr.iter().map(|value| {
+                            Object::Array(arr.iter().map(|value| {
                                 match value {
                                     Value::Null => Object::Null,
                                     Value::Bool(_) => Object::Bool(value.as_bool().unwrap()),
@@ -62,18 +63,18 @@ pub fn parse_json(args: Vec<Object>) -> Object {
                                     }
                                     Value::Object(_) => parse_json(vec![Object::String(value.to_string())]),
                                 }
-                            }).collect());
+                            }).collect())
                         }
                         _ => {
-                            return Object::Error(format!("Expected an object. Got {}", json_obj));
+                            Object::error(format!("Expected an object. Got {}", json_obj))
                         }
                     }
                 }
                 Err(e) => {
-                    return Object::Error(format!("{}", e));
+                    Object::error(format!("{}", e))
                 }
             }
         }
-        _ => Object::Error(format!("Expected a string. Got {}", args[0]))
+        _ => Object::error(format!("Expected a string. Got {}", args[0]))
     }
 }
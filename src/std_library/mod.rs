@@ -14,7 +14,6 @@ pub mod hash;
 /// * `lib` - The name of the library to load.
 /// # Returns
 /// `HashMap<String, Object>` - The environment with the library loaded.
-
 pub struct Res {
     pub globals: HashMap<String, Object>,
     pub raw: Option<String>,
@@ -1,14 +1,14 @@
 use std::collections::HashMap;
 
-use crate::evaluation::object::Object;
+use crate::evaluation::object::{Arity, Object};
 
 use super::Res;
 
 /// Adds the standard library to the global environment.
 pub fn add_globals() -> Res {
     let mut globals = HashMap::new();
-    globals.insert(String::from("replace"), Object::Inbuilt(replace));
-    globals.insert(String::from("to_string"), Object::Inbuilt(to_string));
+    globals.insert(String::from("replace"), Object::Inbuilt(replace, Arity::exactly(3)));
+    globals.insert(String::from("to_string"), Object::Inbuilt(to_string, Arity::exactly(1)));
     Res {
         globals,
         raw: None,
@@ -16,31 +16,17 @@ pub fn add_globals() -> Res {
 }
 
 pub fn replace(args: Vec<Object>) -> Object {
-    if args.len() != 3 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 3.",
-            args.len()
-        ));
-    }
-
     match &args[0] {
         Object::String(s) => {
             let mut s = s.clone();
             s = s.replace(&args[1].to_string(), &args[2].to_string());
             Object::String(s)
         }
-        o => Object::Error(format!("First argument must be a string. Got {}", o)),
+        o => Object::error(format!("First argument must be a string. Got {}", o)),
     }
 }
 
 pub fn to_string(args: Vec<Object>) -> Object {
-    if args.len() != 1 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 1.",
-            args.len()
-        ));
-    }
-
     match &args[0] {
         Object::String(s) => Object::String(s.clone()),
         Object::Array(a) => {
@@ -53,7 +39,6 @@ pub fn to_string(args: Vec<Object>) -> Object {
         Object::Number(n) => Object::String(n.to_string()),
         Object::Bool(b) => Object::String(b.to_string()),
         Object::Null => Object::String(String::from("null")),
-        Object::Error(e) => Object::String(e.clone()),
         Object::Fn(..) => Object::String(String::from("[Function]")),
         Object::Inbuilt(..) => Object::String(String::from("[Inbuilt Function]")),
         o => Object::String(format!("{}", o))        
@@ -1,13 +1,13 @@
 use std::{format, collections::HashMap};
-use crate::evaluation::object::Object;
+use crate::evaluation::object::{Arity, Object};
 
 use super::Res;
 
 pub fn add_globals() -> Res {
     let mut globals = HashMap::new();
-    globals.insert(String::from("length"), Object::Inbuilt(length));
-    globals.insert(String::from("input"), Object::Inbuilt(input));
-    globals.insert(String::from("sleep"), Object::Inbuilt(sleep));
+    globals.insert(String::from("length"), Object::Inbuilt(length, Arity::exactly(1)));
+    globals.insert(String::from("input"), Object::Inbuilt(input, Arity::exactly(1)));
+    globals.insert(String::from("sleep"), Object::Inbuilt(sleep, Arity::exactly(1)));
     Res { globals, raw: None }
 }
 
@@ -17,17 +17,10 @@ pub fn add_globals() -> Res {
 /// # Returns
 /// `Object` - The length of the array or string.
 pub fn length(args: Vec<Object>) -> Object {
-    if args.len() != 1 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 1.",
-            args.len()
-            
-        ));
-    }
     match &args[0] {
         Object::String(s) => Object::Number(s.len() as f64),
         Object::Array(a) => Object::Number(a.len() as f64),
-        o => Object::Error(format!("Argument must be a string or array. Got {}", o)),
+        o => Object::error(format!("Argument must be a string or array. Got {}", o)),
     }
 }
 
@@ -42,12 +35,6 @@ pub fn input(args: Vec<Object>) -> Object {
 }
 
 pub fn sleep(args: Vec<Object>) -> Object {
-    if args.len() != 1 {
-        return Object::Error(format!(
-            "Wrong number of arguments. Got {}. Expected 1.",
-            args.len()
-        ));
-    }
     if let Object::Number(n) = &args[0] {
         std::thread::sleep(std::time::Duration::from_millis(*n as u64));
     }
@@ -1,6 +1,7 @@
 extern crate ethereal_lang;
 
 use ethereal_lang::ast::Program;
+use ethereal_lang::diagnostic::{diagnostics_to_json, render_diagnostics};
 use ethereal_lang::evaluation::globals::new_globals;
 use ethereal_lang::evaluation::store::Store;
 use ethereal_lang::evaluation::object::Object;
@@ -35,12 +36,7 @@ fn parse(input: &str) -> Result<Program, String> {
     let errors = parser.errors;
 
     if !errors.is_empty() {
-        let msg = errors
-            .into_iter()
-            .map(|e| format!("{}\n", e))
-            .collect::<String>();
-
-        return Err(msg);
+        return Err(render_diagnostics(&errors, input));
     }
 
     Ok(program)
@@ -63,6 +59,22 @@ pub unsafe fn dealloc(ptr: *mut c_void, size: usize) {
     let _buf = Vec::from_raw_parts(ptr, 0, size);
 }
 
+/// Parses the input and returns its diagnostics as a JSON array,
+/// for editors to display.
+///
+/// # Safety
+///
+/// This function is unsafe because it is the caller's responsibility to ensure
+/// that the pointer is valid.
+#[no_mangle]
+pub unsafe fn check(input_ptr: *mut c_char) -> *mut c_char {
+    let input = CStr::from_ptr(input_ptr).to_string_lossy().into_owned();
+    let mut parser = Parser::new(Lexer::new(input));
+    parser.parse_program();
+
+    string_to_ptr(diagnostics_to_json(&parser.errors).to_string())
+}
+
 /// # Safety
 ///
 /// This function is unsafe because it is the caller's responsibility to ensure 
@@ -0,0 +1,101 @@
+use std::rc::Rc;
+
+use ethereal_lang::{
+    ast::span::Span,
+    diagnostic::{self, diagnostics_to_json, render_diagnostics, Diagnostic},
+    lexer::Lexer,
+    parser::Parser,
+};
+use serde_json::json;
+
+/// The diagnostics reported for `input`, parsed as `main.etrl`.
+fn diagnostics(input: &str) -> Vec<Diagnostic> {
+    let mut parser = Parser::new(Lexer::new_with_file(input.to_string(), "main.etrl"));
+    parser.parse_program();
+    parser.errors
+}
+
+fn span(line: usize, column: usize, start: usize, end: usize) -> Span {
+    Span::new(Rc::from("main.etrl"), line, column, start, end)
+}
+
+#[test]
+fn renders_the_line_with_a_caret() {
+    let source = "set x 1;";
+    let errors = diagnostics(source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].render(source),
+        "error[E0001]: Expected next token to be Assign, got Number(1.0) instead\n \
+         --> main.etrl:1:7\n  \
+         |\n\
+         1 | set x 1;\n  \
+         |       ^ expected Assign\n"
+    );
+}
+
+#[test]
+fn underlines_the_whole_span() {
+    let source = "set a = 1;\nset n total;";
+    let errors = diagnostics(source);
+    assert_eq!(errors[0].code, diagnostic::UNEXPECTED_TOKEN);
+    let rendered = errors[0].render(source);
+    assert!(rendered.ends_with("2 | set n total;\n  |       ^^^^^ expected Assign\n"), "{}", rendered);
+}
+
+#[test]
+fn renders_secondary_labels_and_notes() {
+    let source = "set a = 1;\n".repeat(9) + "\tfoo(bar)";
+    let error = Diagnostic::error(diagnostic::UNEXPECTED_TOKEN, String::from("bad call"), span(10, 6, 104, 107))
+        .with_primary_label("this argument")
+        .with_label(span(10, 2, 100, 103), "this function")
+        .with_note("a note");
+    assert_eq!(
+        error.render(&source),
+        "error[E0001]: bad call\n  \
+         --> main.etrl:10:6\n   \
+         |\n\
+         10 | \tfoo(bar)\n   \
+         | \t--- this function\n\
+         10 | \tfoo(bar)\n   \
+         | \t    ^^^ this argument\n   \
+         = note: a note\n"
+    );
+}
+
+#[test]
+fn renders_each_diagnostic_separately() {
+    let source = "set a 1;\nset b 2;";
+    let errors = diagnostics(source);
+    assert_eq!(errors.len(), 2);
+    let rendered = render_diagnostics(&errors, source);
+    assert_eq!(rendered, format!("{}\n{}", errors[0].render(source), errors[1].render(source)));
+    assert_eq!(rendered.matches("error[").count(), 2);
+}
+
+#[test]
+fn serializes_to_json() {
+    let error = Diagnostic::error(diagnostic::INVALID_PARAMETER, String::from("bad parameter"), span(2, 3, 7, 9))
+        .with_primary_label("here")
+        .with_label(span(1, 1, 0, 1), "opened here")
+        .with_note("a note");
+    let location = |line: usize, column: usize, start: usize, end: usize| {
+        json!({ "file": "main.etrl", "line": line, "column": column, "start": start, "end": end })
+    };
+    assert_eq!(
+        error.to_json(),
+        json!({
+            "severity": "error",
+            "code": "E0002",
+            "message": "bad parameter",
+            "span": location(2, 3, 7, 9),
+            "label": "here",
+            "labels": [{ "span": location(1, 1, 0, 1), "message": "opened here" }],
+            "notes": ["a note"],
+        })
+    );
+    let bare = Diagnostic::error(diagnostic::UNEXPECTED_TOKEN, String::from("x"), span(1, 1, 0, 0));
+    assert_eq!(bare.to_json()["label"], json!(null));
+    assert_eq!(diagnostics_to_json(&[error.clone(), bare.clone()]), json!([error.to_json(), bare.to_json()]));
+    assert_eq!(diagnostics_to_json(&[]), json!([]));
+}
@@ -0,0 +1,535 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ethereal_lang::{
+    evaluation::{object::Object, store::Store, Eval},
+    lexer::Lexer,
+    parser::Parser,
+};
+
+/// Runs `input` and returns the value of its last statement.
+fn run(input: &str) -> Object {
+    let mut parser = Parser::new(Lexer::new(input.to_string()));
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty(), "{}: {:?}", input, parser.errors);
+    let mut eval = Eval::new(Rc::new(RefCell::new(Store::new())));
+    eval.eval(program).unwrap_or(Object::Null)
+}
+
+fn number(n: f64) -> Object {
+    Object::Number(n)
+}
+
+fn string(s: &str) -> Object {
+    Object::String(s.to_string())
+}
+
+/// Runs `input` and checks that it fails with an error mentioning `message`.
+fn assert_error(input: &str, message: &str) {
+    match run(input) {
+        e @ Object::Error(..) => assert!(e.to_string().contains(message), "{}: {}", input, e),
+        o => panic!("{}: expected an error, got {}", input, o),
+    }
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    let calls = "set calls = 0; set f = fun () { anew calls = calls + 1; true };\n";
+    let run_with = |expr: &str| run(&format!("{}{}", calls, expr));
+    assert_eq!(run_with("false && f(); calls"), number(0.0));
+    assert_eq!(run_with("true || f(); calls"), number(0.0));
+    assert_eq!(run_with("true && f(); calls"), number(1.0));
+    assert_eq!(run_with("false || f(); calls"), number(1.0));
+    assert_eq!(run_with("f() && f() && false && f(); calls"), number(2.0));
+    assert_eq!(run("false && missing"), Object::Bool(false));
+    assert_eq!(run("true || missing"), Object::Bool(true));
+    assert_error("true && missing", "identifier not found");
+}
+
+#[test]
+fn logical_operators_return_booleans() {
+    let cases = [
+        ("1 && 2", true),
+        ("1 && false", false),
+        ("false && 1", false),
+        ("0 || false", true),
+        ("false || false", false),
+        ("\"\" || false", true),
+        ("false || \"x\"", true),
+        ("[] && {}", true),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), Object::Bool(expected), "{}", input);
+    }
+}
+
+#[test]
+fn while_loops() {
+    assert_eq!(run("set i = 0; while (i < 5) { anew i = i + 1; } i"), number(5.0));
+    assert_eq!(run("set i = 10; while (i < 5) { anew i = i + 1; } i"), number(10.0));
+    assert_eq!(run("set i = 0; while (true) { anew i = i + 1; if (i == 3) { break; } } i"), number(3.0));
+    let skip_odd = "set i = 0; set sum = 0; while (i < 6) { anew i = i + 1; if (i % 2 == 1) { continue; } anew sum = sum + i; } sum";
+    assert_eq!(run(skip_odd), number(12.0));
+    assert_eq!(run("set f = fun () { set i = 0; while (true) { anew i = i + 1; if (i == 4) { return i * 10; } } }; f()"), number(40.0));
+}
+
+#[test]
+fn for_loops() {
+    let cases = [
+        ("set sum = 0; for (n in [1, 2, 3]) { anew sum = sum + n; } sum", number(6.0)),
+        ("set sum = 0; for (i, n in [10, 20, 30]) { anew sum = sum + i * n; } sum", number(80.0)),
+        ("set s = \"\"; for (c in \"abc\") { anew s = c + s; } s", string("cba")),
+        ("set s = 0; for (i, c in \"abc\") { anew s = s + i; } s", number(3.0)),
+        ("set sum = 0; for (n in 4) { anew sum = sum + n; } sum", number(6.0)),
+        ("set sum = 0; for (n in 0) { anew sum = sum + 1; } sum", number(0.0)),
+        ("set sum = 0; for (n in 1..=3) { anew sum = sum + n; } sum", number(6.0)),
+        ("set sum = 0; for (v in {\"a\": 1}) { anew sum = v; } sum", string("a")),
+        ("set sum = 0; for (k, v in {\"a\": 1, \"b\": 2}) { anew sum = sum + v; } sum", number(3.0)),
+        ("set n = 0; for (k in {\"a\": 1, \"b\": 2}) { if (k in {\"a\": 0, \"b\": 0}) { anew n = n + 1; } } n", number(2.0)),
+        ("set last = 0; for (n in [1, 2, 3, 4]) { if (n == 3) { break; } anew last = n; } last", number(2.0)),
+        ("set sum = 0; for (n in [1, 2, 3, 4]) { if (n % 2 == 0) { continue; } anew sum = sum + n; } sum", number(4.0)),
+        ("set f = fun () { for (n in [5, 6]) { return n; } }; f()", number(5.0)),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), expected, "{}", input);
+    }
+    assert_error("for (x in true) { }", "cannot iterate over true");
+}
+
+#[test]
+fn for_loop_variables_are_scoped_to_the_loop() {
+    assert_eq!(run("set i = 100; for (i in [1, 2, 3]) { 0 }; i"), number(100.0));
+    assert_eq!(run("set k = 1; set v = 2; for (k, v in [5]) { 0 }; [k, v]"), Object::Array(vec![number(1.0), number(2.0)]));
+}
+
+#[test]
+fn ranges() {
+    // Appends each number as a digit, so the result reads as the sequence walked.
+    let digits = |range: &str| run(&format!("set out = 0; for (n in {}) {{ anew out = out * 10 + n; }} out", range));
+    assert_eq!(digits("1..4"), number(123.0));
+    assert_eq!(digits("1..=4"), number(1234.0));
+    assert_eq!(digits("4..1"), number(0.0));
+    assert_eq!(digits("3..=3"), number(3.0));
+    assert_eq!(run("set sum = 0; for (i, n in 5..8) { anew sum = sum + i * n; } sum"), number(20.0));
+    assert_eq!(run("set n = 0; for (i in 0..1e12) { anew n = i; if (i == 3) { break; } } n"), number(3.0));
+    assert_eq!(run("typeof (1..2)"), Object::String(String::from("range")));
+}
+
+#[test]
+fn indexing_and_slicing() {
+    let numbers = |ns: &[f64]| Object::Array(ns.iter().map(|n| number(*n)).collect());
+    let cases = [
+        ("[1, 2, 3][0]", number(1.0)),
+        ("[1, 2, 3][-1]", number(3.0)),
+        ("[1, 2, 3][-3]", number(1.0)),
+        ("[1, 2, 3][3]", Object::Null),
+        ("[1, 2, 3][-4]", Object::Null),
+        ("[1, 2, 3, 4][1:3]", numbers(&[2.0, 3.0])),
+        ("[1, 2, 3, 4][:2]", numbers(&[1.0, 2.0])),
+        ("[1, 2, 3, 4][2:]", numbers(&[3.0, 4.0])),
+        ("[1, 2, 3, 4][-2:]", numbers(&[3.0, 4.0])),
+        ("[1, 2, 3, 4][:-1]", numbers(&[1.0, 2.0, 3.0])),
+        ("[1, 2, 3, 4][-10:10]", numbers(&[1.0, 2.0, 3.0, 4.0])),
+        ("[1, 2, 3, 4][3:1]", numbers(&[])),
+        ("[1, 2, 3, 4][9:]", numbers(&[])),
+        ("\"hello\"[1:4]", string("ell")),
+        ("\"hello\"[-3:]", string("llo")),
+        ("\"héllo\"[:2]", string("hé")),
+        ("\"hello\"[10:]", string("")),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), expected, "{}", input);
+    }
+    assert_error("[1, 2][\"a\":]", "slice index must be a number");
+    assert_error("5[1:]", "slice operator not supported");
+}
+
+#[test]
+fn tail_of_short_arrays() {
+    assert_eq!(run("[].tail()"), Object::Array(vec![]));
+    assert_eq!(run("[1].tail()"), Object::Array(vec![]));
+    assert_eq!(run("[1, 2, 3].tail()"), Object::Array(vec![number(2.0), number(3.0)]));
+}
+
+#[test]
+fn compound_assignment() {
+    let cases = [
+        ("set x = 1; x += 2; x", 3.0),
+        ("set x = 5; x -= 2; x", 3.0),
+        ("set x = 5; x *= 2; x", 10.0),
+        ("set x = 5; x /= 2; x", 2.5),
+        ("set x = 5; x %= 2; x", 1.0),
+        ("set x = 1; x <<= 4; x", 16.0),
+        ("set x = 16; x >>= 2; x", 4.0),
+        ("set x = 6; x &= 3; x", 2.0),
+        ("set x = 6; x |= 1; x", 7.0),
+        ("set x = 6; x ^= 3; x", 5.0),
+        ("set x = 1; x++; x++; x", 3.0),
+        ("set x = 1; x--; x", 0.0),
+        ("set x = 1; anew x += 1; x", 2.0),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), number(expected), "{}", input);
+    }
+}
+
+#[test]
+fn compound_assignment_on_elements_and_fields() {
+    assert_eq!(run("set a = [1, 2]; a[1] += 10; a[1]"), number(12.0));
+    assert_eq!(run("set a = [1, 2]; a[-1]++; a"), Object::Array(vec![number(1.0), number(3.0)]));
+    assert_eq!(run("set o = {\"n\": {\"m\": 2}}; o.n.m *= 3; o.n.m"), number(6.0));
+    assert_eq!(run("set s = \"a\"; s += \"b\"; s"), Object::String(String::from("ab")));
+}
+
+#[test]
+fn compound_assignment_evaluates_the_target_once() {
+    let counter = "set calls = 0; set f = fun () { anew calls = calls + 1; 0 };\n";
+    let run_with = |expr: &str| run(&format!("{}{}", counter, expr));
+    assert_eq!(run_with("set a = [1, 2]; a[f()] += 10; [a, calls]"), Object::Array(vec![Object::Array(vec![number(11.0), number(2.0)]), number(1.0)]));
+    assert_eq!(run_with("set a = [5]; a[f()]++; [a, calls]"), Object::Array(vec![Object::Array(vec![number(6.0)]), number(1.0)]));
+    assert_eq!(run_with("set o = {\"k\": [1]}; o[\"k\"][f()] *= 3; [o.k, calls]"), Object::Array(vec![Object::Array(vec![number(3.0)]), number(1.0)]));
+}
+
+#[test]
+fn compound_assignment_needs_a_binding() {
+    assert_error("missing += 1;", "identifier not found");
+}
+
+#[test]
+fn ternary() {
+    assert_eq!(run("true ? 1 : 2"), number(1.0));
+    assert_eq!(run("false ? 1 : 2"), number(2.0));
+    assert_eq!(run("set x = 5; x > 3 ? x > 4 ? 3 : 2 : 1"), number(3.0));
+    assert_eq!(run("set f = fun (n) { n < 2 ? n : f(n - 1) + f(n - 2) }; f(10)"), number(55.0));
+}
+
+#[test]
+fn ternary_only_evaluates_the_branch_taken() {
+    assert_eq!(run("true ? 1 : missing"), number(1.0));
+    assert_eq!(run("false ? missing : 2"), number(2.0));
+}
+
+#[test]
+fn if_is_a_value() {
+    let cases = [
+        ("set x = if (true) { 1 } else { 2 }; x", number(1.0)),
+        ("set x = if (false) { 1 } else { 2 }; x", number(2.0)),
+        ("set x = if (false) { 1 }; x", Object::Null),
+        ("set x = if (true) { set y = 1; }; x", Object::Null),
+        ("set x = if (true) { set y = 4; y * 2 } else { 0 }; x", number(8.0)),
+        ("set x = if (false) { 1 } else if (true) { 2 } else { 3 }; x", number(2.0)),
+        ("set id = fun (v) { v }; id(if (true) { 1 } else { 2 })", number(1.0)),
+        ("[if (true) { 1 } else { 2 }, 3][0]", number(1.0)),
+        ("{\"k\": if (false) { 1 } else { 2 }}[\"k\"]", number(2.0)),
+        ("if (true) { 1 } else { 2 } + 10", number(11.0)),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn match_patterns() {
+    let describe = r#"
+        set describe = fun (v) {
+            match (v) {
+                0 => "zero",
+                -1 => "minus one",
+                1..10 => "small",
+                10..=100 => "medium",
+                typeof "number" => "big",
+                "hi" => "greeting",
+                [] => "empty",
+                [x] => x,
+                [first, ...rest] => rest,
+                { "type": "circle", r } => r,
+                { "type": t } if t == "square" => { set s = "four sides"; s },
+                _ => "other",
+            }
+        };
+    "#;
+    let cases = [
+        ("0", string("zero")),
+        ("-1", string("minus one")),
+        ("9.5", string("small")),
+        ("100", string("medium")),
+        ("101", string("big")),
+        ("\"hi\"", string("greeting")),
+        ("[]", string("empty")),
+        ("[7]", number(7.0)),
+        ("[1, 2, 3]", Object::Array(vec![number(2.0), number(3.0)])),
+        ("{\"type\": \"circle\", \"r\": 2}", number(2.0)),
+        ("{\"type\": \"square\"}", string("four sides")),
+        ("{\"type\": \"triangle\"}", string("other")),
+        ("true", string("other")),
+    ];
+    for (value, expected) in cases {
+        let input = format!("{} describe({})", describe, value);
+        assert_eq!(run(&input), expected, "{}", value);
+    }
+}
+
+#[test]
+fn match_binds_only_when_the_arm_matches() {
+    assert_eq!(run("set a = 0; match ([1, 2]) { [a, 3] => a, _ => 0 }; a"), number(0.0));
+    assert_eq!(run("match (3) { x if x > 5 => 1, x => x * 2 }"), number(6.0));
+    assert_eq!(run("set x = 5; match (3) { x => x }"), number(3.0));
+    assert_eq!(run("set x = 5; match (3) { x => x }; x"), number(5.0));
+    assert_eq!(run("set y = 1; match ([9]) { [y] if y > 100 => 1, _ => 2 }; y"), number(1.0));
+}
+
+#[test]
+fn non_exhaustive_match_is_an_error() {
+    assert_error("match (5) { 1 => 1, [] => 2 }", "non-exhaustive match");
+}
+
+#[test]
+fn destructuring_set() {
+    assert_eq!(run("set [a, b] = [1, 2]; a + b"), number(3.0));
+    assert_eq!(run("set [a, [b, c]] = [1, [2, 3]]; a + b + c"), number(6.0));
+    assert_eq!(run("set [a, ...rest] = [1, 2, 3]; rest"), Object::Array(vec![number(2.0), number(3.0)]));
+    assert_eq!(run("set { \"age\": a } = {\"name\": \"n\", \"age\": 3}; a"), number(3.0));
+    assert_eq!(run("set { age } = {\"age\": 4}; age"), number(4.0));
+}
+
+#[test]
+fn destructuring_defaults() {
+    assert_eq!(run("set [a, b = 5] = [1]; b"), number(5.0));
+    assert_eq!(run("set [a, b = 5] = [1, 2]; b"), number(2.0));
+    assert_eq!(run("set { age = 18 } = {}; age"), number(18.0));
+}
+
+#[test]
+fn destructuring_errors() {
+    let cases = [
+        ("set [a, b] = [1];", "missing element 1"),
+        ("set { age } = {};", "missing key \"age\""),
+        ("set [a] = 1;", "cannot destructure number"),
+        ("set f = fun ([a, b]) { a }; f([1]);", "missing element 1"),
+    ];
+    for (input, message) in cases {
+        assert_error(input, message);
+    }
+}
+
+#[test]
+fn destructuring_parameters() {
+    let input = "set f = fun ([a, b], { c }, d) { a + b + c + d }; f([1, 2], {\"c\": 3}, 4)";
+    assert_eq!(run(input), number(10.0));
+}
+
+#[test]
+fn default_and_rest_parameters() {
+    let f = "set f = fun (x, y = x + 10, ...rest) { [x, y, rest] };";
+    let array = |items: &[f64]| Object::Array(items.iter().map(|n| number(*n)).collect());
+    let call = |args: &str| run(&format!("{} f({})", f, args));
+    assert_eq!(call("1"), Object::Array(vec![number(1.0), number(11.0), array(&[])]));
+    assert_eq!(call("1, 2, 3, 4"), Object::Array(vec![number(1.0), number(2.0), array(&[3.0, 4.0])]));
+    assert_eq!(call("...[5, 6, 7]"), Object::Array(vec![number(5.0), number(6.0), array(&[7.0])]));
+    assert_eq!(call("y: 3, x: 2"), Object::Array(vec![number(2.0), number(3.0), array(&[])]));
+}
+
+#[test]
+fn arity_errors() {
+    let cases = [
+        ("set f = fun (a, b = 1) { a }; f();", "expected 1 to 2, got 0"),
+        ("set f = fun (a) { a }; f(1, 2);", "expected 1, got 2"),
+        ("set f = fun (a, b) { a }; f(1, a: 2);", "given both by position and by name"),
+        ("set f = fun (a, b = 1) { a }; f(a: 1, c: 2);", "unknown named argument: c"),
+        ("set f = fun (a) { a }; f(...1);", "only arrays can be spread"),
+        ("\"abc\".upper(1);", "expected 0, got 1"),
+    ];
+    for (input, message) in cases {
+        assert_error(input, message);
+    }
+}
+
+#[test]
+fn null_and_optional_chaining() {
+    let data = "set data = {\"user\": {\"name\": \"ada\", \"tags\": [\"a\"]}, \"none\": null};";
+    let run_with = |expr: &str| run(&format!("{} {}", data, expr));
+    assert_eq!(run("null"), Object::Null);
+    assert_eq!(run_with("data.none"), Object::Null);
+    assert_eq!(run_with("data.none == null"), Object::Bool(true));
+    assert_eq!(run_with("data.user != null"), Object::Bool(true));
+    assert_eq!(run_with("data.user?.name"), Object::String(String::from("ada")));
+    assert_eq!(run_with("data.none?.name"), Object::Null);
+    assert_eq!(run_with("data.none?.[0]"), Object::Null);
+    assert_eq!(run_with("data.user.tags?.[0]"), Object::String(String::from("a")));
+    assert_eq!(run_with("data.none?.upper()"), Object::Null);
+    assert_eq!(run_with("data.user.greet?.()"), Object::Null);
+    assert_eq!(run_with("data.none ?? 5"), number(5.0));
+    assert_eq!(run_with("data.user.name ?? 5"), Object::String(String::from("ada")));
+    assert_eq!(run("false ?? 5"), Object::Bool(false));
+    assert_eq!(run("match (null) { null => 1, _ => 2 }"), number(1.0));
+    assert!(matches!(run_with("data.none.name"), Object::Error(..)));
+}
+
+#[test]
+fn indexing_errors() {
+    assert_eq!(run("typeof null?.[0]"), string("null"));
+    let cases = [
+        ("null[0]", "cannot index null"),
+        ("set x = null; typeof x[0]", "cannot index null"),
+        ("set x = null; 1 + x[0]", "cannot index null"),
+        ("1?.[0]", "cannot index number"),
+        ("set n = 1; [-1] * n?.[0]", "cannot index number"),
+        ("[1][\"a\"]", "cannot index array with string"),
+        ("undefined[0]", "identifier not found: undefined"),
+        ("[1][undefined]", "identifier not found: undefined"),
+    ];
+    for (input, message) in cases {
+        assert_error(input, message);
+    }
+}
+
+#[test]
+fn template_literals() {
+    assert_eq!(
+        run("set name = \"ada\"; set age = 36; `Hello ${name}, you are ${age + 1}`"),
+        string("Hello ada, you are 37")
+    );
+    assert_eq!(run("`${[1, 2]} ${null} ${true}`"), string("[1, 2] null true"));
+    assert_eq!(run("`outer ${ `inner ${ {\"a\": 1}[\"a\"] }` }`"), string("outer inner 1"));
+    assert_error("`${missing}`", "identifier not found");
+}
+
+#[test]
+fn throw_and_catch() {
+    let f = "set f = fun (x) { if (x > 1) { throw {\"kind\": \"TooBig\", \"message\": `x is ${x}`}; } x };";
+    assert_eq!(run(&format!("{} try {{ f(1) }} catch (e) {{ 0 }}", f)), number(1.0));
+    assert_eq!(run(&format!("{} try {{ f(5) }} catch (e) {{ e.kind }}", f)), string("TooBig"));
+    assert_eq!(run(&format!("{} try {{ f(5) }} catch (e) {{ e.message }}", f)), string("x is 5"));
+    assert_eq!(run("try { throw \"oops\"; } catch (e) { [e.message, e.kind] }"), Object::Array(vec![string("oops"), string("thrown")]));
+    assert_eq!(run("try { missing } catch (e) { e.kind }"), string("runtime"));
+    assert!(matches!(
+        run("try { missing } catch (e) { e.message }"),
+        Object::String(msg) if msg == "identifier not found: missing"
+    ));
+    assert_eq!(run("try {\n  missing\n} catch (e) { e.location }"), string("2:3"));
+    assert_eq!(run("try { 1 + 2;\n throw 1; } catch (e) { e.location }"), string("2:2"));
+    assert!(matches!(run("throw 42;"), Object::Thrown(value, _, frames) if *value == number(42.0) && frames.is_empty()));
+}
+
+#[test]
+fn catch_binding_is_scoped_to_the_handler() {
+    assert_eq!(run("set e = \"outer\"; try { throw \"boom\"; } catch (e) { e.message }"), string("boom"));
+    assert_eq!(run("set e = \"outer\"; try { throw \"boom\"; } catch (e) { 0 }; e"), string("outer"));
+    assert_eq!(run("set n = 0; try { throw 1; } catch (e) { anew n = 2; }; n"), number(2.0));
+}
+
+#[test]
+fn finally_always_runs() {
+    let input = "set log = []; set f = fun () { try { return 1; } finally { anew log = log.push(\"f\"); } }; [f(), log]";
+    let log = Object::Array(vec![Object::String(String::from("f"))]);
+    assert_eq!(run(input), Object::Array(vec![number(1.0), log]));
+    assert_eq!(run("set n = 0; try { throw 1; } catch { anew n = 1; } finally { anew n += 10; } n"), number(11.0));
+    assert_eq!(run("set n = 0; try { try { throw 1; } finally { anew n = 5; } } catch { n }"), number(5.0));
+    assert!(matches!(run("try { 1 } finally { throw 2; }"), Object::Thrown(value, ..) if *value == number(2.0)));
+}
+
+#[test]
+fn errors_carry_the_call_stack() {
+    let functions = "set inner = fun (x) { x + missing };\nset outer = fun (x) { inner(x) };\n";
+    match run(&format!("{}outer(1)", functions)) {
+        e @ Object::Error(..) => {
+            let msg = e.to_string();
+            let lines: Vec<&str> = msg.lines().collect();
+            assert_eq!(lines.len(), 3, "{}", msg);
+            assert!(lines[0].ends_with("identifier not found: missing"), "{}", msg);
+            assert_eq!(lines[1], "    at inner (2:23)");
+            assert_eq!(lines[2], "    at outer (3:1)");
+        }
+        o => panic!("expected an error, got {}", o),
+    }
+    let stack = |frames: &[&str]| Object::Array(frames.iter().map(|f| Object::String(f.to_string())).collect());
+    assert_eq!(
+        run(&format!("{}try {{ outer(1) }} catch (e) {{ e.stack }}", functions)),
+        stack(&["inner (2:23)", "outer (3:7)"])
+    );
+    assert_eq!(
+        run("set f = fun () { throw 1; };\ntry { (fun () { f() })() } catch (e) { e.stack }"),
+        stack(&["f (2:17)", "<anonymous> (2:8)"])
+    );
+    // Messages are kept apart from the stack, so text that looks like a frame stays in the message.
+    let fake = "set f = fun () { throw \"bad\\n    at nowhere (9:9)\"; };\ntry { f() } catch (e) { [e.message, e.stack] }";
+    assert_eq!(
+        run(fake),
+        Object::Array(vec![Object::String(String::from("bad\n    at nowhere (9:9)")), stack(&["f (2:7)"])])
+    );
+    match run(&format!("{}try {{ outer(1) }} catch {{ 0 }};\ninner(1)", functions)) {
+        Object::Error(_, _, frames) => assert_eq!(frames.iter().map(|f| f.to_string()).collect::<Vec<_>>(), ["inner (4:1)"]),
+        o => panic!("expected an error, got {}", o),
+    }
+}
+
+#[test]
+fn structs() {
+    let point = "struct Point { x, y }\nset p = Point(1, 2);\n";
+    let run_with = |expr: &str| run(&format!("{}{}", point, expr));
+    assert_eq!(run_with("[p.x, p[\"y\"]]"), Object::Array(vec![number(1.0), number(2.0)]));
+    assert_eq!(run_with("typeof p"), string("Point"));
+    assert_eq!(run_with("p.to_string()"), string("Point { x: 1, y: 2 }"));
+    assert_eq!(run_with("Point(y: 2, x: 1) == p"), Object::Bool(true));
+    assert_eq!(run_with("anew p.x = 10; p.y += 1; [p.x, p.y]"), Object::Array(vec![number(10.0), number(3.0)]));
+    assert_eq!(run_with("struct Other { x, y } Other(1, 2) == p"), Object::Bool(false));
+}
+
+#[test]
+fn struct_errors() {
+    let cases = [
+        ("struct P { x } P(1).y;", "P has no field y"),
+        ("struct P { x } set p = P(1); anew p.y = 2;", "P has no field y"),
+        ("struct P { x } P();", "expected 1, got 0"),
+        ("struct P { x } P(z: 1);", "unknown named argument: z"),
+        ("struct P { x, x }", "duplicate field x"),
+    ];
+    for (input, message) in cases {
+        assert_error(input, message);
+    }
+}
+
+#[test]
+fn classes() {
+    let counter = "class Counter {
+    count,
+    step,
+    fun init(start = 0, step = 1) { self.count = start; self.step = step; }
+    fun inc() { self.count += self.step; return self; }
+    fun get() { self.count }
+    fun twice() { self.inc(); self.inc(); }
+}
+set c = Counter();
+";
+    let run_with = |expr: &str| run(&format!("{}{}", counter, expr));
+    assert_eq!(run_with("c.get()"), number(0.0));
+    assert_eq!(run_with("c.inc(); c.inc(); c.get()"), number(2.0));
+    assert_eq!(run_with("c.twice(); c.count"), number(2.0));
+    assert_eq!(run_with("Counter(10, step: 5).inc().get()"), number(15.0));
+    assert_eq!(run_with("set cs = [Counter(), Counter(7)]; cs[1].inc(); [cs[0].count, cs[1].count]"), Object::Array(vec![number(0.0), number(8.0)]));
+    assert_eq!(run_with("set d = c; d.inc(); [c.count, d.count]"), Object::Array(vec![number(0.0), number(1.0)]));
+    assert_eq!(
+        run_with("set n = 0; set g = fun () { anew n = n + 1; 1 }; set cs = [Counter(), Counter()]; cs[g()].inc(); [n, cs[1].count]"),
+        Object::Array(vec![number(1.0), number(1.0)])
+    );
+    assert_eq!(run_with("typeof c"), string("Counter"));
+
+    // Without `init`, a class is constructed like a struct.
+    assert_eq!(run("class P { x, y fun sum() { self.x + self.y } } P(1, 2).sum()"), number(3.0));
+}
+
+#[test]
+fn class_errors() {
+    let cases = [
+        ("class C { x fun f() { self.y } } C(1).f();", "C has no field y"),
+        ("class C { x fun f() { self.y = 1; } } set c = C(1); c.f();", "C has no field y"),
+        ("class C { x fun f() {} } C(1).g();", "C has no method g"),
+        ("class C { x fun f(a) {} } C(1).f();", "expected 1, got 0"),
+        ("class C { x fun x() {} }", "duplicate method x"),
+        ("class C { fun f() {} fun f() {} }", "duplicate method f"),
+        ("class C { fun f() { missing } } C().f();", "at C.f (1:33)"),
+        ("fun() { self }();", "identifier not found: self"),
+    ];
+    for (input, message) in cases {
+        assert_error(input, message);
+    }
+}
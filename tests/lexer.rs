@@ -0,0 +1,258 @@
+use ethereal_lang::{
+    ast::{token::TokenKind, Infix},
+    diagnostic,
+    lexer::Lexer,
+};
+
+fn tokens(input: &str) -> Vec<TokenKind> {
+    let mut lexer = Lexer::new(input.to_string());
+    let mut kinds = vec![];
+    loop {
+        let kind = lexer.next_token().kind;
+        if kind == TokenKind::Eof {
+            break;
+        }
+        kinds.push(kind);
+    }
+    assert!(lexer.errors.is_empty(), "{:?}", lexer.errors);
+    kinds
+}
+
+/// Lexes `input`, returning its tokens and the codes of the errors reported along the way.
+fn lex(input: &str) -> (Vec<TokenKind>, Vec<&'static str>) {
+    let mut lexer = Lexer::new(input.to_string());
+    let mut kinds = vec![];
+    loop {
+        let kind = lexer.next_token().kind;
+        if kind == TokenKind::Eof {
+            break;
+        }
+        kinds.push(kind);
+    }
+    (kinds, lexer.errors.iter().map(|e| e.code).collect())
+}
+
+fn string(value: &str) -> TokenKind {
+    TokenKind::String(value.to_string())
+}
+
+fn ident(name: &str) -> TokenKind {
+    TokenKind::Ident(name.to_string())
+}
+
+#[test]
+fn escape_sequences() {
+    let cases = [
+        (r#""a\nb""#, "a\nb"),
+        (r#""a\tb""#, "a\tb"),
+        (r#""\r\0""#, "\r\0"),
+        (r#""\\ \" \'""#, "\\ \" '"),
+        (r#""\u{41}\u{e9}\u{1F600}""#, "A\u{e9}\u{1F600}"),
+        ("`\\` \\$`", "` $"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(tokens(input), vec![string(expected)], "{}", input);
+    }
+}
+
+#[test]
+fn invalid_escapes() {
+    let cases = [
+        (r#""a\qb""#, "a\\qb"),
+        (r#""\u{110000}""#, "\u{FFFD}"),
+        (r#""\u{}""#, "\u{FFFD}"),
+        (r#""\u41""#, "\u{FFFD}41"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(lex(input), (vec![string(expected)], vec![diagnostic::INVALID_ESCAPE]), "{}", input);
+    }
+}
+
+#[test]
+fn raw_strings() {
+    assert_eq!(tokens(r#"r"C:\path\n""#), vec![string("C:\\path\\n")]);
+    assert_eq!(tokens(r###"r#"say "hi""#"###), vec![string("say \"hi\"")]);
+    assert_eq!(tokens(r###"r##"a "# b"##"###), vec![string("a \"# b")]);
+    assert_eq!(tokens("r + 1"), vec![ident("r"), TokenKind::Plus, TokenKind::Number(1.0)]);
+}
+
+#[test]
+fn triple_quoted_strings() {
+    assert_eq!(tokens("\"\"\"\nline 1\n  \"line\" 2\"\"\""), vec![string("line 1\n  \"line\" 2")]);
+    assert_eq!(tokens("\"\"\"a\\tb\"\"\""), vec![string("a\tb")]);
+    assert_eq!(tokens("\"\"\"\"\"\" x"), vec![string(""), ident("x")]);
+}
+
+#[test]
+fn unterminated_strings() {
+    let cases = [
+        ("\"abc", "abc"),
+        ("r\"abc", "abc"),
+        ("r#\"abc\"", "abc\""),
+        ("\"\"\"abc\"", "abc\""),
+        ("`abc", "abc"),
+    ];
+    for (input, value) in cases {
+        assert_eq!(lex(input), (vec![string(value)], vec![diagnostic::UNTERMINATED_STRING]), "{}", input);
+    }
+}
+
+#[test]
+fn number_literals() {
+    let cases = [
+        ("42", 42.0),
+        ("2.75", 2.75),
+        ("0", 0.0),
+        ("1e9", 1e9),
+        ("2.5E-3", 2.5e-3),
+        ("1e+2", 100.0),
+        ("0xff", 255.0),
+        ("0XFF", 255.0),
+        ("0b1010", 10.0),
+        ("0o17", 15.0),
+        ("1_000_000", 1_000_000.0),
+        ("0xFF_FF", 65535.0),
+        ("0b1111_0000", 240.0),
+        ("1_0.2_5", 10.25),
+        ("1__0", 10.0),
+    ];
+    for (input, value) in cases {
+        assert_eq!(lex(input), (vec![TokenKind::Number(value)], vec![]), "{}", input);
+    }
+    assert_eq!(tokens("1..3"), vec![TokenKind::Number(1.0), TokenKind::DotDot, TokenKind::Number(3.0)]);
+    assert_eq!(tokens("x.0"), vec![ident("x"), TokenKind::Dot, TokenKind::Number(0.0)]);
+}
+
+#[test]
+fn malformed_number_literals() {
+    let cases = ["1.2.3", "10px", "0x", "0xfg", "0b102", "0o8", "1e", "1_", "0x_1", "0x1_0000_0000_0000_0000"];
+    for input in cases {
+        assert_eq!(lex(input), (vec![TokenKind::Number(0.0)], vec![diagnostic::INVALID_NUMBER]), "{}", input);
+    }
+    // The sign is not part of the malformed literal when no digits follow it.
+    assert_eq!(lex("1e+"), (vec![TokenKind::Number(0.0), TokenKind::Plus], vec![diagnostic::INVALID_NUMBER]));
+}
+
+#[test]
+fn identifiers_may_contain_digits() {
+    assert_eq!(
+        tokens("vec2 sha256 x1 a1b2c3 _0"),
+        vec![ident("vec2"), ident("sha256"), ident("x1"), ident("a1b2c3"), ident("_0")]
+    );
+}
+
+#[test]
+fn identifiers_do_not_start_with_digits() {
+    assert_eq!(
+        tokens("2 + x2"),
+        vec![TokenKind::Number(2.0), TokenKind::Plus, ident("x2")]
+    );
+}
+
+#[test]
+fn digits_end_at_operators() {
+    assert_eq!(
+        tokens("x1+y2(z3)"),
+        vec![
+            ident("x1"),
+            TokenKind::Plus,
+            ident("y2"),
+            TokenKind::LeftParen,
+            ident("z3"),
+            TokenKind::RightParen,
+        ]
+    );
+}
+
+#[test]
+fn keywords_still_match() {
+    assert_eq!(
+        tokens("set fun if else return true false"),
+        vec![
+            TokenKind::Set,
+            TokenKind::Func,
+            TokenKind::If,
+            TokenKind::Else,
+            TokenKind::Return,
+            TokenKind::Boolean(true),
+            TokenKind::Boolean(false),
+        ]
+    );
+}
+
+#[test]
+fn keywords_with_digits_are_identifiers() {
+    assert_eq!(
+        tokens("set1 if2 return0 true1"),
+        vec![ident("set1"), ident("if2"), ident("return0"), ident("true1")]
+    );
+}
+
+#[test]
+fn unicode_identifiers() {
+    assert_eq!(tokens("größe2 π"), vec![ident("größe2"), ident("π")]);
+}
+
+#[test]
+fn compound_assignment_operators() {
+    let ops = [
+        ("+=", Infix::Plus),
+        ("-=", Infix::Minus),
+        ("*=", Infix::Times),
+        ("/=", Infix::Divide),
+        ("%=", Infix::Modulo),
+        ("<<=", Infix::LeftShift),
+        (">>=", Infix::RightShift),
+        ("&=", Infix::AND),
+        ("|=", Infix::OR),
+        ("^=", Infix::XOR),
+    ];
+    for (op, infix) in ops {
+        assert_eq!(
+            tokens(&format!("x {} 1", op)),
+            vec![ident("x"), TokenKind::CompoundAssign(infix), TokenKind::Number(1.0)],
+            "{}",
+            op
+        );
+    }
+}
+
+#[test]
+fn increment_and_decrement() {
+    assert_eq!(tokens("i++"), vec![ident("i"), TokenKind::Increment]);
+    assert_eq!(tokens("i--"), vec![ident("i"), TokenKind::Decrement]);
+    assert_eq!(
+        tokens("a - -b"),
+        vec![ident("a"), TokenKind::Minus, TokenKind::Minus, ident("b")]
+    );
+}
+
+#[test]
+fn null_and_optional_chaining() {
+    assert_eq!(
+        tokens("a?.b ?? null"),
+        vec![ident("a"), TokenKind::QuestionDot, ident("b"), TokenKind::NullCoalesce, TokenKind::Null]
+    );
+    assert_eq!(
+        tokens("a ? b : c"),
+        vec![ident("a"), TokenKind::Question, ident("b"), TokenKind::Colon, ident("c")]
+    );
+}
+
+#[test]
+fn template_literals() {
+    assert_eq!(
+        tokens("`a ${x + {}} b ${y} c`"),
+        vec![
+            TokenKind::TemplateStart(String::from("a ")),
+            ident("x"),
+            TokenKind::Plus,
+            TokenKind::LeftBrace,
+            TokenKind::RightBrace,
+            TokenKind::TemplateMiddle(String::from(" b ")),
+            ident("y"),
+            TokenKind::TemplateEnd(String::from(" c")),
+        ]
+    );
+    assert_eq!(tokens("`plain \\${x}`"), vec![TokenKind::String(String::from("plain ${x}"))]);
+}
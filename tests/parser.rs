@@ -0,0 +1,250 @@
+use ethereal_lang::{
+    ast::{ExprKind, Ident, Infix, Literal, StatementKind},
+    diagnostic,
+    lexer::Lexer,
+    parser::Parser,
+};
+
+fn parse(input: &str) -> (Vec<StatementKind>, Vec<&'static str>, Vec<usize>) {
+    let mut parser = Parser::new(Lexer::new(input.to_string()));
+    let program = parser.parse_program();
+    let statements = program.statements.into_iter().map(|s| s.kind).collect();
+    let codes = parser.errors.iter().map(|e| e.code).collect();
+    let lines = parser.errors.iter().map(|e| e.span.line).collect();
+    (statements, codes, lines)
+}
+
+#[test]
+fn one_bad_line_yields_one_error() {
+    let (statements, codes, lines) = parse("set a = 1 + ;\nset b = 2;\nput(b);");
+    assert_eq!(codes, vec![diagnostic::EXPECTED_EXPRESSION]);
+    assert_eq!(lines, vec![1]);
+    assert_eq!(statements.len(), 2);
+}
+
+#[test]
+fn reports_every_bad_statement() {
+    let input = "set a = 1 + ;\nset b 2;\nset c = * 3;\nset d = 4;";
+    let (statements, codes, lines) = parse(input);
+    assert_eq!(
+        codes,
+        vec![
+            diagnostic::EXPECTED_EXPRESSION,
+            diagnostic::UNEXPECTED_TOKEN,
+            diagnostic::EXPECTED_EXPRESSION,
+        ]
+    );
+    assert_eq!(lines, vec![1, 2, 3]);
+    assert!(matches!(&statements[..], [StatementKind::Set(name, _)] if name.0 == "d"));
+}
+
+#[test]
+fn recovers_inside_blocks() {
+    let input = "set f = fun (x) {\n    set y = * 2;\n    return x;\n};\nset g = 1;";
+    let (statements, codes, lines) = parse(input);
+    assert_eq!(codes, vec![diagnostic::EXPECTED_EXPRESSION]);
+    assert_eq!(lines, vec![2]);
+    assert_eq!(statements.len(), 2);
+    match &statements[0] {
+        StatementKind::Set(_, expr) => match &expr.kind {
+            ExprKind::Fun { body, .. } => {
+                assert_eq!(body.len(), 1);
+                assert!(matches!(body[0].kind, StatementKind::Return(Some(_))));
+            }
+            e => panic!("expected a function, got {:?}", e),
+        },
+        s => panic!("expected a set statement, got {:?}", s),
+    }
+}
+
+#[test]
+fn resyncs_on_statement_keywords() {
+    let (statements, codes, _) = parse("set a = (1 + 2\nset b = 3;\nif (b) { put(b) }");
+    assert_eq!(codes, vec![diagnostic::UNEXPECTED_TOKEN]);
+    assert_eq!(statements.len(), 2);
+
+    let followers = [
+        "struct P { x }",
+        "class C { x fun f() { self.x } }",
+        "throw 1;",
+        "try { 1 } catch { 2 }",
+        "anew b = 1;",
+    ];
+    for follower in followers {
+        let (statements, codes, _) = parse(&format!("set a = (1 + 2\n{}", follower));
+        assert_eq!(codes, vec![diagnostic::UNEXPECTED_TOKEN], "{}", follower);
+        assert_eq!(statements.len(), 1, "{}", follower);
+    }
+}
+
+#[test]
+fn bad_parameters_do_not_cascade() {
+    let (statements, codes, lines) = parse("set f = fun (x, 1) { return x; };\nset g = 2;");
+    assert_eq!(codes, vec![diagnostic::INVALID_PARAMETER]);
+    assert_eq!(lines, vec![1]);
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn missing_semicolon_at_end_of_input() {
+    let (statements, codes, _) = parse("set a = 1");
+    assert!(codes.is_empty());
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn bare_return() {
+    let (statements, codes, _) = parse("return;");
+    assert!(codes.is_empty());
+    assert!(matches!(statements[0], StatementKind::Return(None)));
+}
+
+#[test]
+fn unterminated_string_is_reported_once() {
+    for input in ["put(\"abc)", "set s = [\"abc", "set s = \"abc"] {
+        let (_, codes, _) = parse(input);
+        assert_eq!(codes, vec![diagnostic::UNTERMINATED_STRING], "{}", input);
+    }
+}
+
+#[test]
+fn comments_are_kept_as_trivia() {
+    let input = "// line\nset a = /* inline /* nested */ */ 1;\n/// Adds one.\n/// Twice.\nset inc = fun (x) { x + 1 };";
+    let mut parser = Parser::new(Lexer::new(input.to_string()));
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty());
+    assert_eq!(program.statements.len(), 2);
+    assert_eq!(program.statements[0].doc, None);
+    assert_eq!(program.statements[1].doc.as_deref(), Some("Adds one.\nTwice."));
+    let comments: Vec<(&str, bool)> =
+        program.comments.iter().map(|c| (c.text.as_str(), c.doc)).collect();
+    assert_eq!(
+        comments,
+        vec![
+            ("// line", false),
+            ("/* inline /* nested */ */", false),
+            ("Adds one.", true),
+            ("Twice.", true),
+        ]
+    );
+}
+
+#[test]
+fn unterminated_block_comment() {
+    let (statements, codes, lines) = parse("set a = 1;\n/* never closed\nset b = 2;");
+    assert_eq!(codes, vec![diagnostic::UNTERMINATED_COMMENT]);
+    assert_eq!(lines, vec![2]);
+    assert_eq!(statements.len(), 1);
+
+    let (_, codes, _) = parse("set a = /* never closed");
+    assert_eq!(codes, vec![diagnostic::UNTERMINATED_COMMENT]);
+}
+
+#[test]
+fn slices() {
+    for (input, has_start, has_end) in [
+        ("a[1:2];", true, true),
+        ("a[1:];", true, false),
+        ("a[:2];", false, true),
+        ("a[:];", false, false),
+    ] {
+        let (statements, codes, _) = parse(input);
+        assert!(codes.is_empty(), "{}", input);
+        match &statements[0] {
+            StatementKind::Expression(expr) => match &expr.kind {
+                ExprKind::Slice { start, end, .. } => {
+                    assert_eq!((start.is_some(), end.is_some()), (has_start, has_end), "{}", input)
+                }
+                e => panic!("expected a slice, got {:?}", e),
+            },
+            s => panic!("expected an expression, got {:?}", s),
+        }
+    }
+}
+
+#[test]
+fn assignment_targets() {
+    let (statements, codes, _) = parse("x = 1;\nanew x[0] = 2;\ncfg[\"db\"][\"port\"] = 3;");
+    assert!(codes.is_empty());
+    assert_eq!(statements.len(), 3);
+    assert!(statements.iter().all(|s| matches!(s, StatementKind::Anew(..))));
+
+    let (statements, codes, lines) = parse("f() = 1;\n1 + x = 2;\nset y = 3;");
+    assert_eq!(codes, vec![diagnostic::INVALID_ASSIGNMENT; 2]);
+    assert_eq!(lines, vec![1, 2]);
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn compound_assignment() {
+    let (statements, codes, _) = parse("x += 2;\nanew a[0] *= 3;\no.n <<= 1;\ni++;\ni--;");
+    assert!(codes.is_empty());
+    let expected = [
+        ("x", Infix::Plus, 2.0),
+        ("a", Infix::Times, 3.0),
+        ("o", Infix::LeftShift, 1.0),
+        ("i", Infix::Plus, 1.0),
+        ("i", Infix::Minus, 1.0),
+    ];
+    assert_eq!(statements.len(), expected.len());
+    for (statement, (name, op, amount)) in statements.iter().zip(expected) {
+        match statement {
+            StatementKind::CompoundAssign(target, infix, value) => {
+                let root = match &target.kind {
+                    ExprKind::Index { array, .. } => &array.kind,
+                    ExprKind::Member { object, .. } => &object.kind,
+                    kind => kind,
+                };
+                assert_eq!(*root, ExprKind::Ident(Ident(name.to_string())));
+                assert_eq!(*infix, op);
+                assert_eq!(value.kind, ExprKind::Literal(Literal::Number(amount)));
+            }
+            s => panic!("expected a compound assignment, got {:?}", s),
+        }
+    }
+}
+
+#[test]
+fn compound_assignment_needs_a_target() {
+    let (statements, codes, _) = parse("f() += 1;\n1++;\nset y = 3;");
+    assert_eq!(codes, vec![diagnostic::INVALID_ASSIGNMENT; 2]);
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn bad_patterns_are_reported() {
+    let (statements, codes, lines) = parse("match (x) { + => 1 };\nmatch (x) { [a, ...] => 1, _ => 2 };");
+    assert_eq!(codes, vec![diagnostic::INVALID_PATTERN]);
+    assert_eq!(lines, vec![1]);
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn rest_parameter_must_be_last() {
+    let (statements, codes, lines) = parse("set f = fun (a, ...b, c) { a };\nset g = fun (a = 1, ...b) { a };");
+    assert_eq!(codes, vec![diagnostic::UNEXPECTED_TOKEN]);
+    assert_eq!(lines, vec![1]);
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn try_needs_catch_or_finally() {
+    let (statements, codes, lines) = parse("try { 1 }\nset a = try { 1 } catch { 2 };\ntry { 1 } finally { 2 }");
+    assert_eq!(codes, vec![diagnostic::UNEXPECTED_TOKEN]);
+    assert_eq!(lines, vec![2]);
+    assert_eq!(statements.len(), 2);
+}
+
+#[test]
+fn class_members() {
+    let (statements, codes, _) = parse("class C {\n    a, b\n    fun f(x) { x }\n    c\n    fun g() {}\n}");
+    assert!(codes.is_empty(), "{:?}", codes);
+    match &statements[..] {
+        [StatementKind::Class(name, fields, methods)] => {
+            assert_eq!(name.0, "C");
+            assert_eq!(fields.iter().map(|f| f.0.as_str()).collect::<Vec<_>>(), ["a", "b", "c"]);
+            assert_eq!(methods.iter().map(|m| m.0 .0.as_str()).collect::<Vec<_>>(), ["f", "g"]);
+        }
+        s => panic!("expected a class, got {:?}", s),
+    }
+}
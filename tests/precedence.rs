@@ -0,0 +1,148 @@
+use ethereal_lang::{
+    ast::{Argument, Expr, ExprKind, Literal, StatementKind},
+    lexer::Lexer,
+    parser::Parser,
+};
+
+/// Every binary operator with its level, loosest first.
+/// Operators on the same row bind equally tight.
+const LEVELS: &[&[&str]] = &[
+    &["??"],
+    &["||"],
+    &["&&"],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<", ">", "<=", ">=", "~"],
+    &["..", "..="],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+fn level(op: &str) -> usize {
+    LEVELS.iter().position(|ops| ops.contains(&op)).unwrap()
+}
+
+/// Renders an expression fully parenthesized, e.g. `(a + (b * c))`.
+fn render(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Ident(ident) => ident.0.clone(),
+        ExprKind::Literal(Literal::Number(n)) => n.to_string(),
+        ExprKind::Prefix(prefix, right) => format!("({}{})", prefix, render(right)),
+        ExprKind::Infix(infix, left, right) => {
+            format!("({} {} {})", render(left), infix, render(right))
+        }
+        ExprKind::Range { start, end, inclusive } => {
+            let op = if *inclusive { "..=" } else { ".." };
+            format!("({} {} {})", render(start), op, render(end))
+        }
+        ExprKind::Ternary { cond, then, else_ } => {
+            format!("({} ? {} : {})", render(cond), render(then), render(else_))
+        }
+        ExprKind::Call { function, args, optional } => {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| match arg {
+                    Argument::Positional(e) => render(e),
+                    Argument::Spread(e) => format!("...{}", render(e)),
+                    Argument::Named(name, e) => format!("{}: {}", name.0, render(e)),
+                })
+                .collect();
+            let dot = if *optional { "?." } else { "" };
+            format!("{}{}({})", render(function), dot, args.join(", "))
+        }
+        ExprKind::Index { array, index, optional } => {
+            let dot = if *optional { "?." } else { "" };
+            format!("{}{}[{}]", render(array), dot, render(index))
+        }
+        ExprKind::Member { object, property, optional } => {
+            let dot = if *optional { "?." } else { "." };
+            format!("{}{}{}", render(object), dot, property.0)
+        }
+        kind => panic!("unexpected expression {:?}", kind),
+    }
+}
+
+fn parse(input: &str) -> String {
+    let mut parser = Parser::new(Lexer::new(input.to_string()));
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty(), "{}: {:?}", input, parser.errors);
+    assert_eq!(program.statements.len(), 1, "{}", input);
+    match &program.statements[0].kind {
+        StatementKind::Expression(expr) => render(expr),
+        s => panic!("expected an expression, got {:?}", s),
+    }
+}
+
+#[test]
+fn every_operator_pair() {
+    let ops: Vec<&str> = LEVELS.iter().flat_map(|ops| ops.iter().copied()).collect();
+    for first in &ops {
+        for second in &ops {
+            let input = format!("a {} b {} c", first, second);
+            let expected = if level(first) >= level(second) {
+                format!("((a {} b) {} c)", first, second)
+            } else {
+                format!("(a {} (b {} c))", first, second)
+            };
+            assert_eq!(parse(&input), expected, "{}", input);
+        }
+    }
+}
+
+#[test]
+fn prefix_binds_tighter_than_binary() {
+    for op in LEVELS.iter().flat_map(|ops| ops.iter()) {
+        assert_eq!(parse(&format!("-a {} b", op)), format!("((-a) {} b)", op));
+        assert_eq!(parse(&format!("a {} !b", op)), format!("(a {} (!b))", op));
+    }
+}
+
+#[test]
+fn calls_and_indexes_bind_tightest() {
+    for op in LEVELS.iter().flat_map(|ops| ops.iter()) {
+        assert_eq!(parse(&format!("f(a) {} b[1]", op)), format!("(f(a) {} b[1])", op));
+    }
+    assert_eq!(parse("-f(a)[0]"), "(-f(a)[0])");
+    assert_eq!(parse("-a.b.c(d)[0] * e.f"), "((-a.b.c(d)[0]) * e.f)");
+    assert_eq!(parse("1.5.round() + a.b"), "(1.5.round() + a.b)");
+    assert_eq!(parse("a?.b?.[0]?.(c) ?? d"), "(a?.b?.[0]?.(c) ?? d)");
+}
+
+#[test]
+fn grouping_overrides_precedence() {
+    assert_eq!(parse("(a + b) * c"), "((a + b) * c)");
+    assert_eq!(parse("1 << (2 + 3)"), "(1 << (2 + 3))");
+    assert_eq!(parse("(a & b) == c"), "((a & b) == c)");
+}
+
+#[test]
+fn two_character_operators_are_single_tokens() {
+    assert_eq!(parse("a <= b"), "(a <= b)");
+    assert_eq!(parse("a >= b"), "(a >= b)");
+    assert_eq!(parse("a<<b"), "(a << b)");
+    assert_eq!(parse("a>>b"), "(a >> b)");
+    assert_eq!(parse("a < -b"), "(a < (-b))");
+    assert_eq!(parse("a > -b"), "(a > (-b))");
+    assert_eq!(parse("a??b"), "(a ?? b)");
+    assert_eq!(parse("a ?.b"), "a?.b");
+}
+
+#[test]
+fn ternary_binds_loosest() {
+    for op in LEVELS.iter().flat_map(|ops| ops.iter()) {
+        assert_eq!(
+            parse(&format!("a {op} b ? c {op} d : e {op} f")),
+            format!("((a {op} b) ? (c {op} d) : (e {op} f))")
+        );
+    }
+}
+
+#[test]
+fn ternary_is_right_associative() {
+    assert_eq!(parse("a ? b : c ? d : e"), "(a ? b : (c ? d : e))");
+    assert_eq!(parse("a ? b ? c : d : e"), "(a ? (b ? c : d) : e)");
+    assert_eq!(parse("(a ? b : c) ? d : e"), "((a ? b : c) ? d : e)");
+}